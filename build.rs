@@ -1,39 +1,161 @@
-// Build script that generates man pages for the CLI using clap_mangen.
-// It includes the same derive-based CLI definitions from src/cli.rs so that
-// the Command layout is a single source of truth.
+// Build script that records build details for the binary (see src/version.rs)
+// and, with the `docs-gen` feature, the man page header: date, authors, bug
+// address and copyright (see src/man.rs).
+//
+// Man pages and shell completions are generated on demand by `cargo xtask`
+// (see xtask/) from the library's `Cli::command()`, so normal builds stay quiet.
 
 use std::env;
-use std::error::Error;
-use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-// Include the CLI definitions directly so this build script (a separate crate)
-// can use the same clap derive types. Requires `clap` in [build-dependencies].
-mod cli {
-    include!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/cli.rs"));
+fn main() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("set by cargo"));
+    emit_build_info(&manifest_dir);
+    // Only the pages use these; minimal builds skip the git lookup.
+    if env::var_os("CARGO_FEATURE_DOCS_GEN").is_some() {
+        println!("cargo:rustc-env=MYCLI_MAN_DATE={}", man_date(&manifest_dir));
+        emit_man_credits();
+    }
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    // Always rerun if CLI file changes
-    println!("cargo:rerun-if-changed=src/cli.rs");
+// The `.TH` source and manual and the texts of the AUTHORS, REPORTING BUGS
+// and COPYRIGHT sections, from the package metadata. Forks can override each
+// with the variable of the same name; an empty value drops the field or
+// section.
+fn emit_man_credits() {
+    let package = |key: &str| env::var(format!("CARGO_PKG_{key}")).unwrap_or_default();
+    let name = package("NAME");
+    let source = format!("{name} {}", package("VERSION"));
+    let mut letters = name.chars();
+    let manual = match letters.next() {
+        Some(first) => format!("{}{} Manual", first.to_uppercase(), letters.as_str()),
+        None => String::new(),
+    };
+    let authors = package("AUTHORS").split(':').collect::<Vec<_>>().join(", ");
+    let repository = package("REPOSITORY");
+    let bugs = match repository.trim_end_matches('/') {
+        "" => String::new(),
+        repo => format!("Report bugs at {repo}/issues."),
+    };
+    let copyright = match package("LICENSE") {
+        license if license.is_empty() => String::new(),
+        license => format!("Licensed under {license}."),
+    };
 
-    // Determine output directory: use target/man under the workspace for convenience.
-    // Using OUT_DIR is also fine; target/man is easier to discover.
-    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR")?);
-    let out_dir = manifest_dir.join("target").join("man");
-    fs::create_dir_all(&out_dir)?;
+    for (var, derived) in [
+        ("MYCLI_MAN_SOURCE", source),
+        ("MYCLI_MAN_MANUAL", manual),
+        ("MYCLI_MAN_AUTHORS", authors),
+        ("MYCLI_MAN_BUGS", bugs),
+        ("MYCLI_MAN_COPYRIGHT", copyright),
+    ] {
+        println!("cargo:rerun-if-env-changed={var}");
+        let text = env::var(var).unwrap_or(derived);
+        println!("cargo:rustc-env={var}={}", text.replace('\n', " "));
+    }
+}
 
-    // Build the clap::Command from the derive type.
-    let cmd = <cli::Cli as clap::CommandFactory>::command();
+// Expose build details to the binary (see src/version.rs) as compile-time env vars.
+fn emit_build_info(manifest_dir: &Path) {
+    let git_dir = manifest_dir.join(".git");
+    // Only watch git metadata that exists; a missing path would rerun every
+    // build. Refs move to packed-refs on `git gc`, so watch that too.
+    for path in [
+        git_dir.join("HEAD"),
+        git_dir.join("refs"),
+        git_dir.join("packed-refs"),
+    ] {
+        if path.exists() {
+            println!("cargo:rerun-if-changed={}", path.display());
+        }
+    }
+    // The package metadata the man page credits come from.
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
 
-    // Generate a man page for the root and all subcommands recursively.
-    // clap_mangen::generate_to will walk the subcommands if you pass the root Command.
-    clap_mangen::generate_to(cmd, &out_dir)?;
+    let git_hash = command_output(Command::new("git").args(["rev-parse", "--short", "HEAD"]));
+    let rustc = command_output(
+        Command::new(env::var("RUSTC").unwrap_or_else(|_| "rustc".into())).arg("-V"),
+    );
+    // `default` is the feature set, not a feature of its own.
+    let mut features: Vec<String> = env::vars()
+        .filter_map(|(key, _)| {
+            key.strip_prefix("CARGO_FEATURE_")
+                .filter(|f| *f != "DEFAULT")
+                .map(|f| f.to_lowercase().replace('_', "-"))
+        })
+        .collect();
+    features.sort();
 
     println!(
-        "cargo:warning=Generated man pages to {}",
-        out_dir.display()
+        "cargo:rustc-env=MYCLI_GIT_HASH={}",
+        git_hash.as_deref().unwrap_or("unknown")
+    );
+    println!("cargo:rustc-env=MYCLI_BUILD_DATE={}", build_date());
+    println!(
+        "cargo:rustc-env=MYCLI_RUSTC={}",
+        rustc.as_deref().unwrap_or("unknown")
     );
+    println!(
+        "cargo:rustc-env=MYCLI_TARGET={}",
+        env::var("TARGET").unwrap_or_default()
+    );
+    println!("cargo:rustc-env=MYCLI_FEATURES={}", features.join(","));
+}
+
+// Date for the `.TH` line: SOURCE_DATE_EPOCH, else the last commit touching
+// the CLI definition, else today. Never the build time when either is known,
+// so rebuilding the same source gives byte-identical pages.
+fn man_date(manifest_dir: &Path) -> String {
+    let committed = || {
+        command_output(Command::new("git").current_dir(manifest_dir).args([
+            "log",
+            "-1",
+            "--format=%ct",
+            "--",
+            "src/cli.rs",
+        ]))?
+        .parse()
+        .ok()
+    };
+    source_date_epoch()
+        .or_else(committed)
+        .map_or_else(build_date, format_date)
+}
+
+fn command_output(cmd: &mut Command) -> Option<String> {
+    let output = cmd.output().ok().filter(|o| o.status.success())?;
+    let text = String::from_utf8(output.stdout).ok()?;
+    Some(text.trim().to_owned()).filter(|t| !t.is_empty())
+}
+
+fn source_date_epoch() -> Option<u64> {
+    env::var("SOURCE_DATE_EPOCH").ok()?.parse().ok()
+}
+
+// `SOURCE_DATE_EPOCH` when set (reproducible builds), otherwise today.
+fn build_date() -> String {
+    let secs = source_date_epoch().unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs())
+    });
+    format_date(secs)
+}
 
-    Ok(())
+// Format seconds since the Unix epoch as `YYYY-MM-DD` (UTC).
+// Days-to-civil conversion from Howard Hinnant's date algorithms.
+fn format_date(secs: u64) -> String {
+    let days = (secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
 }
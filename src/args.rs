@@ -0,0 +1,29 @@
+// Raw argument scanning for the few global flags that must take effect before
+// clap has parsed anything (help rendering, error formatting).
+
+use std::ffi::OsString;
+
+/// Value of `--<long> <value>` or `--<long>=<value>` in the process arguments.
+/// Scanning stops at `--`; the last occurrence wins, like clap's own parsing.
+pub fn early_value(long: &str) -> Option<String> {
+    find_value(std::env::args_os().skip(1), long)
+}
+
+fn find_value(args: impl IntoIterator<Item = OsString>, long: &str) -> Option<String> {
+    let flag = format!("--{long}");
+    let prefix = format!("--{long}=");
+
+    let mut found = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let arg = arg.to_string_lossy();
+        if arg == "--" {
+            break;
+        } else if arg == flag {
+            found = args.next().map(|v| v.to_string_lossy().into_owned());
+        } else if let Some(value) = arg.strip_prefix(&prefix) {
+            found = Some(value.to_owned());
+        }
+    }
+    found
+}
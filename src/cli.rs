@@ -1,22 +1,185 @@
-// Shared CLI definition for both runtime (src/main.rs) and build script (build.rs).
-// Keep this file self-contained: only depend on `clap` and avoid referencing other crate-local types.
+// Shared CLI definition for both runtime (src/main.rs) and the doc generators (xtask/).
+
+use std::ffi::OsString;
+#[cfg(feature = "docs-gen")]
+use std::path::PathBuf;
 
 use clap::{Args, Parser, Subcommand, ValueEnum};
 
-// Longer description used for the top-level man page section.
-const LONG_ABOUT: &str = r#"
-mycli is a tiny example CLI demonstrating auto-generated man pages with clap and clap_mangen.
+// Longer description used for the top-level man page section. `mycli docs`
+// only exists with the docs-gen feature, so the text about it is passed in.
+macro_rules! long_about {
+    ($install:literal, $docs:literal) => {
+        concat!(
+            r#"mycli is a tiny example CLI demonstrating auto-generated man pages with clap and clap_mangen.
 
 It showcases:
     - Nested subcommands (e.g., `config get`, `config set`)
     - Rich help/usage text derived from a single source of truth
-    - Build-time man page generation to `target/man/`
+    - Generated man pages"#,
+            $install,
+            r#"
 
 Top-level commands:
     - config: manage configuration values (get/set)
     - server: run a demo server (addr/port/verbosity)
     - remote: add or remove a remote by name
-"#;
+    - plugins: list external `mycli-<name>` plugins found on PATH
+    - doctor: check the environment (editor, man pages, port, plugins)
+    - version: print detailed build information
+"#,
+            $docs
+        )
+    };
+}
+
+#[cfg(feature = "docs-gen")]
+const LONG_ABOUT: &str = long_about!(
+    ", installable with `mycli docs --install`",
+    "    - docs: find, open or regenerate the man pages\n"
+);
+#[cfg(not(feature = "docs-gen"))]
+const LONG_ABOUT: &str = long_about!("", "");
+
+// Usage examples appended to each command's help, and rendered as the EXAMPLES
+// section of its man page by src/man.rs.
+// Every example is a `# description` line followed by the literal invocation.
+const ROOT_EXAMPLES: &str = "\
+Examples:
+  # Show the help of every command at once
+  mycli --help-all
+
+  # Read a configuration value as JSON
+  mycli config get core.editor --format json
+";
+
+const CONFIG_EXAMPLES: &str = "\
+Examples:
+  # Read a value
+  mycli config get core.editor
+
+  # Write a value to the global scope
+  mycli config set --global core.editor vim
+";
+
+const CONFIG_GET_EXAMPLES: &str = "\
+Examples:
+  # Print a value as plain text
+  mycli config get core.editor
+
+  # Print a value as JSON
+  mycli config get core.editor --format json
+";
+
+const CONFIG_SET_EXAMPLES: &str = "\
+Examples:
+  # Set a value for the local scope
+  mycli config set core.editor vim
+
+  # Set a value for every repository
+  mycli config set --global user.name \"Jane Doe\"
+";
+
+const PLUGINS_EXAMPLES: &str = "\
+Examples:
+  # List plugins found on MYCLI_PLUGIN_PATH and PATH
+  mycli plugins list
+
+  # One NUL-ended record per plugin, e.g. for `xargs -0`
+  mycli plugins list --null | xargs -0 -n1 printf '%s\\n'
+
+  # Run the `mycli-hello` plugin with its own arguments
+  mycli hello --name world
+";
+
+const DOCTOR_EXAMPLES: &str = "\
+Examples:
+  # Run every check
+  mycli doctor
+
+  # Machine-readable report for CI
+  mycli doctor --format json
+
+  # One line per check as it finishes
+  mycli doctor --format ndjson | jq -c 'select(.status != \"pass\")'
+";
+
+// Long help of `--strict`, the list of every warning it turns into an error.
+const STRICT_LONG_HELP: &str = "\
+Treat warnings as errors and exit with status 1. Affected are exactly:
+
+- doctor: checks reporting WARN fail the run like FAIL checks.
+
+- docs --generate, --markdown and --book: a plugin named in MYCLI_DOC_PLUGINS \
+that is not installed or does not answer --plugin-describe stops the run \
+instead of being listed without a description.";
+
+// Long description of `version`, documenting the stable JSON schema.
+const VERSION_LONG_ABOUT: &str = r#"Print detailed build information.
+
+Unlike `--version`, the output of this command is meant to be parsed. With
+`--format json` it is a single object on one line (indented with `--pretty`)
+whose fields are only ever added to:
+
+    {
+      "version": "0.1.0",             crate version
+      "git_hash": "a65c863",          short commit hash, or "unknown"
+      "build_date": "2026-01-31",     YYYY-MM-DD, from SOURCE_DATE_EPOCH when set
+      "rustc": "rustc 1.xx.y (...)",  compiler used for the build
+      "target": "x86_64-unknown-linux-gnu",
+      "features": []                  enabled cargo features
+    }
+
+`--format toml` emits the same keys as a TOML document."#;
+
+const VERSION_EXAMPLES: &str = "\
+Examples:
+  # Human-readable build details
+  mycli version
+
+  # Build details for a bug report script
+  mycli version --format json
+";
+
+#[cfg(feature = "docs-gen")]
+const DOCS_EXAMPLES: &str = "\
+Examples:
+  # Open the page of `mycli config set`
+  mycli docs config.set
+
+  # Print where the root page is installed
+  mycli docs --path
+
+  # Write every page into a directory
+  mycli docs --generate man
+
+  # Show where --install would put the pages of the current user
+  mycli docs --install --user --dry-run
+
+  # Write the Markdown reference, e.g. to commit it
+  mycli docs --markdown docs/cli
+
+  # Write an mdBook of the reference and build it as HTML
+  mycli docs --book book && mdbook build book
+";
+
+const SERVER_EXAMPLES: &str = "\
+Examples:
+  # Listen on the default address and port
+  mycli server
+
+  # Listen on all interfaces with extra logging
+  mycli server --addr 0.0.0.0 --port 9000 -vv
+";
+
+const REMOTE_EXAMPLES: &str = "\
+Examples:
+  # Add a remote over SSH
+  mycli remote origin --url git@example.com:team/repo.git
+
+  # Remove a remote
+  mycli remote origin --remove
+";
 
 /// mycli — a tiny example CLI used to demonstrate auto-generated man pages.
 #[derive(Debug, Parser)]
@@ -24,12 +187,108 @@ Top-level commands:
     name = "mycli",
     about = "Example CLI with nested subcommands and man page generation",
     long_about = LONG_ABOUT,
-    version
+    after_help = ROOT_EXAMPLES,
+    version,
+    disable_version_flag = true,
+    arg_required_else_help = true
 )]
 pub struct Cli {
+    /// Print the full help of every command and subcommand
+    #[arg(long, help = "Print help for every command and subcommand")]
+    pub help_all: bool,
+
+    /// Print the command tree as JSON for external tooling
+    #[arg(long, hide = true)]
+    pub dump_cli_json: bool,
+
+    /// Wrap help output at this many columns (0 disables wrapping)
+    #[arg(
+        long,
+        global = true,
+        value_name = "COLS",
+        env = "MYCLI_HELP_WIDTH",
+        hide_short_help = true,
+        help = "Wrap help at COLS columns instead of the terminal width (0 disables wrapping)"
+    )]
+    pub help_width: Option<usize>,
+
+    /// Never pipe long output through a pager
+    #[arg(
+        long,
+        global = true,
+        hide_short_help = true,
+        help = "Print long output directly instead of through $MYCLI_PAGER/$PAGER"
+    )]
+    pub no_pager: bool,
+
+    /// Only print primary output and errors
+    #[arg(
+        short,
+        long,
+        global = true,
+        help = "Suppress informational output; values and errors are still printed"
+    )]
+    pub quiet: bool,
+
+    /// Indent JSON output instead of printing it on one line
+    #[arg(
+        long,
+        global = true,
+        hide_short_help = true,
+        help = "Print --format json output indented, one key per line"
+    )]
+    pub pretty: bool,
+
+    /// Fail on problems that are otherwise only warnings
+    #[arg(
+        long,
+        global = true,
+        hide_short_help = true,
+        help = "Treat warnings as errors and exit with status 1",
+        long_help = STRICT_LONG_HELP
+    )]
+    pub strict: bool,
+
+    /// When to color output
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "WHEN",
+        default_value_t = ColorWhen::Auto,
+        hide_short_help = true,
+        help = "Highlight JSON output: on a terminal (auto), always, or never"
+    )]
+    pub color: ColorWhen,
+
+    /// How errors are reported on stderr
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "FORMAT",
+        default_value_t = ErrorFormat::Human,
+        hide_short_help = true,
+        help = "Report errors as human-readable text or as a JSON object"
+    )]
+    pub errors: ErrorFormat,
+
+    /// Print the version (with --verbose, also the compiled-in features)
+    #[arg(short = 'V', long, help = "Print version")]
+    pub version: bool,
+
+    /// With --version, also list the cargo features the binary was built with
+    #[arg(
+        long,
+        requires = "version",
+        hide_short_help = true,
+        help = "With --version, also list the cargo features the binary was built with"
+    )]
+    pub verbose: bool,
+
     /// Top-level subcommand to execute
     #[command(subcommand)]
-    pub command: Commands,
+    pub command: Option<Commands>,
 }
 
 /// All top-level subcommands.
@@ -43,10 +302,35 @@ pub enum Commands {
 
     /// Interact with remotes
     Remote(RemoteCmd),
+
+    /// Manage external `mycli-<name>` plugins
+    Plugins(PluginsCmd),
+
+    /// Diagnose common problems with the environment
+    ///
+    /// With `--format ndjson` every check is printed as soon as it has run,
+    /// as a `{"name", "status", "detail"}` object: the shape of the entries
+    /// of `checks` in `--format json`. There is no summary line; the exit
+    /// status tells whether a check failed.
+    Doctor(DoctorCmd),
+
+    /// Print detailed build information
+    #[command(long_about = VERSION_LONG_ABOUT)]
+    Version(VersionCmd),
+
+    /// Find, open or regenerate the man pages
+    #[cfg(feature = "docs-gen")]
+    Docs(DocsCmd),
+
+    /// Any other name: the `mycli-<name>` plugin, with the name first and
+    /// then the arguments that follow it
+    #[command(external_subcommand)]
+    External(Vec<OsString>),
 }
 
 /// `config` command with nested subcommands.
 #[derive(Debug, Args)]
+#[command(after_help = CONFIG_EXAMPLES)]
 pub struct ConfigCmd {
     /// Action to perform on configuration (get/set)
     #[command(subcommand)]
@@ -65,6 +349,7 @@ pub enum ConfigAction {
 
 /// Arguments for `config get`.
 #[derive(Debug, Args)]
+#[command(after_help = CONFIG_GET_EXAMPLES)]
 pub struct ConfigGet {
     /// Configuration key to read, e.g. "core.editor"
     pub key: String,
@@ -76,6 +361,7 @@ pub struct ConfigGet {
 
 /// Arguments for `config set`.
 #[derive(Debug, Args)]
+#[command(after_help = CONFIG_SET_EXAMPLES)]
 pub struct ConfigSet {
     /// Configuration key to write, e.g. "core.editor"
     pub key: String,
@@ -84,37 +370,81 @@ pub struct ConfigSet {
     pub value: String,
 
     /// Write to the global scope instead of local
-    #[arg(long, help = "Write to the global config scope")] 
+    #[arg(long, help = "Write to the global config scope")]
     pub global: bool,
 }
 
-/// Output format for config get.
+/// Output format for `config get`.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
 pub enum OutputFormat {
-    /// Plain text output (default)
+    /// Plain text output
     Plain,
     /// JSON output
     Json,
 }
 
+/// Format of reports with one record per item, such as `doctor`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub enum ReportFormat {
+    /// Aligned columns and a summary
+    Plain,
+    /// One JSON document with every record and a summary
+    Json,
+    /// One JSON object per line as each record is ready, shaped like the
+    /// records of the json format
+    Ndjson,
+}
+
+/// Error report format selected with `--errors`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub enum ErrorFormat {
+    /// A human-readable sentence
+    Human,
+    /// `{"error": {"code", "message", "hint"}}`
+    Json,
+}
+
+/// When to color output, selected with `--color`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub enum ColorWhen {
+    /// When stdout is a terminal and NO_COLOR is not set
+    Auto,
+    /// Even when piped, e.g. into `less -R`
+    Always,
+    /// Not even on a terminal
+    Never,
+}
+
 /// Arguments for `server` command.
 #[derive(Debug, Args)]
+#[command(after_help = SERVER_EXAMPLES)]
 pub struct ServerCmd {
     /// Port to listen on
     #[arg(short, long, default_value_t = 8080, help = "Port to listen on")]
     pub port: u16,
 
-    /// Bind address
-    #[arg(long, default_value = "127.0.0.1", help = "Bind address")]
+    /// Bind address (advanced: only shown in `--help`)
+    #[arg(
+        long,
+        default_value = "127.0.0.1",
+        help = "Bind address",
+        hide_short_help = true
+    )]
     pub addr: String,
 
     /// Increase output verbosity (-v, -vv)
-    #[arg(short, long, action = clap::ArgAction::Count, help = "Increase verbosity (-v, -vv)")]
+    #[arg(
+        short,
+        long,
+        action = clap::ArgAction::Count,
+        help = "Increase verbosity (-v, -vv); conflicts with --quiet"
+    )]
     pub verbose: u8,
 }
 
 /// Arguments for `remote` command.
 #[derive(Debug, Args)]
+#[command(after_help = REMOTE_EXAMPLES)]
 pub struct RemoteCmd {
     /// Remote name
     pub name: String,
@@ -127,3 +457,142 @@ pub struct RemoteCmd {
     #[arg(long, help = "Remove the remote instead of adding")]
     pub remove: bool,
 }
+
+/// `plugins` command.
+#[derive(Debug, Args)]
+#[command(after_help = PLUGINS_EXAMPLES)]
+pub struct PluginsCmd {
+    /// Action to perform on plugins
+    #[command(subcommand)]
+    pub action: PluginsAction,
+}
+
+/// Subcommands for `plugins`.
+#[derive(Debug, Subcommand)]
+pub enum PluginsAction {
+    /// List discovered plugins and where they were found
+    List(PluginsList),
+}
+
+/// Arguments for `plugins list`.
+#[derive(Debug, Args)]
+pub struct PluginsList {
+    /// End each record with a NUL byte instead of a newline, for `xargs -0`
+    #[arg(
+        short = 'z',
+        long = "null",
+        help = "Print unpadded NAME<TAB>PATH records, each ended by a NUL byte instead of a newline"
+    )]
+    pub null: bool,
+}
+
+/// Arguments for `doctor` command.
+#[derive(Debug, Args)]
+#[command(after_help = DOCTOR_EXAMPLES)]
+pub struct DoctorCmd {
+    /// Report format
+    #[arg(long, value_enum, default_value_t = ReportFormat::Plain, help = "Report format")]
+    pub format: ReportFormat,
+}
+
+/// Arguments for `version` command.
+#[derive(Debug, Args)]
+#[command(after_help = VERSION_EXAMPLES)]
+pub struct VersionCmd {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = VersionFormat::Plain, help = "Output format")]
+    pub format: VersionFormat,
+}
+
+/// Output format for `version`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub enum VersionFormat {
+    /// Aligned `key: value` lines
+    Plain,
+    /// A JSON object
+    Json,
+    /// A TOML document
+    Toml,
+}
+
+/// Arguments for `docs` command.
+#[cfg(feature = "docs-gen")]
+#[derive(Debug, Args)]
+#[command(after_help = DOCS_EXAMPLES)]
+pub struct DocsCmd {
+    /// Command whose page to show, e.g. "config.set" or "config set" (default: mycli)
+    pub command: Vec<String>,
+
+    /// Print the page location instead of opening it
+    #[arg(long, help = "Print the page location instead of opening it")]
+    pub path: bool,
+
+    /// Write every page into DIR instead of opening one
+    #[arg(
+        long,
+        value_name = "DIR",
+        conflicts_with_all = ["command", "path"],
+        help = "Write every page into DIR"
+    )]
+    pub generate: Option<PathBuf>,
+
+    /// Write a Markdown reference of every command into DIR
+    #[arg(
+        long,
+        value_name = "DIR",
+        conflicts_with_all = ["command", "path", "generate"],
+        help = "Write a Markdown reference of every command into DIR"
+    )]
+    pub markdown: Option<PathBuf>,
+
+    /// Write the Markdown reference as an mdBook source tree into DIR
+    #[arg(
+        long,
+        value_name = "DIR",
+        conflicts_with_all = ["command", "path", "generate", "markdown"],
+        help = "Write the Markdown reference as an mdBook source tree into DIR"
+    )]
+    pub book: Option<PathBuf>,
+
+    /// Install every page into PREFIX/man1, or PREFIX/man8 for the server page, and update the man index
+    #[arg(
+        long,
+        conflicts_with_all = ["command", "path", "generate", "markdown", "book"],
+        help = "Install every page into PREFIX/man1, or PREFIX/man8 for the server page, and update the man index"
+    )]
+    pub install: bool,
+
+    /// Man directory to install into (default: /usr/local/share/man)
+    #[arg(
+        long,
+        value_name = "PREFIX",
+        requires = "install",
+        help = "Man directory to install into (default: /usr/local/share/man)"
+    )]
+    pub prefix: Option<PathBuf>,
+
+    /// Install into ~/.local/share/man instead
+    #[arg(
+        long,
+        requires = "install",
+        conflicts_with = "prefix",
+        help = "Install into ~/.local/share/man instead"
+    )]
+    pub user: bool,
+
+    /// Install gzipped pages (mycli.1.gz, ...)
+    #[arg(
+        long,
+        requires = "install",
+        help = "Install gzipped pages (mycli.1.gz, ...)"
+    )]
+    pub gzip: bool,
+
+    /// Print what would be installed without writing anything
+    #[arg(
+        long,
+        requires = "install",
+        help = "Print what would be installed without writing anything"
+    )]
+    pub dry_run: bool,
+}
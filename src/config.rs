@@ -0,0 +1,119 @@
+// `mycli config`: values stored as JSON objects of strings, one file per
+// scope. The local scope is `.mycli/config.json` in the working directory of
+// the `Context`, the global one `config.json` in its config root (see
+// `paths::config_root`). Reads look in the local scope first.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use crate::error::AppError;
+use crate::paths;
+use crate::Context;
+
+/// Where a value is stored.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Scope {
+    /// The working directory, e.g. one repository
+    Local,
+    /// Every directory of the user
+    Global,
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Scope::Local => "local",
+            Scope::Global => "global",
+        })
+    }
+}
+
+/// A stored value and where it came from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Entry {
+    pub key: String,
+    pub value: String,
+    pub scope: Scope,
+    /// The file of `scope`.
+    pub path: PathBuf,
+}
+
+/// The value of `key`, from the local scope if it is set there.
+pub fn get(ctx: &Context, key: &str) -> Result<Entry, AppError> {
+    for scope in [Scope::Local, Scope::Global] {
+        // Without a config root there is no global value to find.
+        let Ok(path) = file(ctx, scope) else {
+            continue;
+        };
+        if let Some(Value::String(value)) = load(&path)?.remove(key) {
+            return Ok(Entry {
+                key: key.to_owned(),
+                value,
+                scope,
+                path,
+            });
+        }
+    }
+    Err(AppError::KeyNotFound(key.to_owned()))
+}
+
+/// Store `value` under `key` in `scope`, creating the file if needed.
+pub fn set(ctx: &Context, key: &str, value: &str, scope: Scope) -> Result<Entry, AppError> {
+    let path = file(ctx, scope)?;
+    let mut values = load(&path)?;
+    values.insert(key.to_owned(), Value::String(value.to_owned()));
+    // Pretty, with a final newline, since people edit these files too.
+    let text = serde_json::to_string_pretty(&values).expect("JSON values serialize") + "\n";
+    path.parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::write(&path, text))
+        .map_err(|source| AppError::Config {
+            path: path.clone(),
+            source,
+        })?;
+    Ok(Entry {
+        key: key.to_owned(),
+        value: value.to_owned(),
+        scope,
+        path,
+    })
+}
+
+// The file of `scope`.
+fn file(ctx: &Context, scope: Scope) -> Result<PathBuf, AppError> {
+    match scope {
+        Scope::Local => Ok(ctx.work_dir.join(paths::LOCAL_CONFIG)),
+        Scope::Global => match &ctx.config_root {
+            Some(root) => Ok(root.join(paths::CONFIG_FILE)),
+            None => Err(AppError::Config {
+                path: PathBuf::from(paths::CONFIG_ROOT).join(paths::CONFIG_FILE),
+                source: io::Error::new(io::ErrorKind::NotFound, "HOME is not set"),
+            }),
+        },
+    }
+}
+
+// The values in `path`; none if it does not exist.
+fn load(path: &Path) -> Result<Map<String, Value>, AppError> {
+    let failed = |source| AppError::Config {
+        path: path.to_owned(),
+        source,
+    };
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Map::new()),
+        Err(e) => return Err(failed(e)),
+    };
+    match serde_json::from_str(&text).map_err(io::Error::from) {
+        Ok(Value::Object(values)) => Ok(values),
+        Ok(_) => Err(failed(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "not a JSON object",
+        ))),
+        Err(e) => Err(failed(e)),
+    }
+}
@@ -0,0 +1,259 @@
+// `mycli docs`: find, open or regenerate the man pages, or write the
+// Markdown reference.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, Stdio};
+use std::thread;
+
+use clap::CommandFactory;
+
+use crate::cli::{Cli, DocsCmd};
+use crate::error::AppError;
+use crate::exit;
+use crate::i18n::{self, Lang};
+use crate::man;
+use crate::man_text;
+use crate::markdown;
+use crate::paths;
+use crate::plugins;
+use crate::ui;
+use crate::{Context, Output};
+
+/// An installed copy of the page for a command path, plain or gzipped, in
+/// `lang` if there is one.
+pub fn installed_page<S: AsRef<str>>(path: &[S], lang: Lang) -> Option<PathBuf> {
+    let file = man::file_name(path);
+    let section = format!("man{}", man::section(path));
+    paths::man_dirs().into_iter().find_map(|dir| {
+        let localized = man::lang_dir(lang).map(|lang| dir.join(lang));
+        localized
+            .into_iter()
+            .chain([dir])
+            .flat_map(|dir| {
+                [
+                    dir.join(&section).join(&file),
+                    dir.join(&section).join(format!("{file}.gz")),
+                ]
+            })
+            .find(|path| path.is_file())
+    })
+}
+
+/// Run `mycli docs`; with `strict`, a warning is an error.
+pub fn run(args: DocsCmd, strict: bool, ctx: &mut Context) -> Result<Output, AppError> {
+    if let Some(dir) = args.generate {
+        let plugins = doc_plugins(ctx, strict)?;
+        return write_all(dir, |cmd, dir| {
+            let header = man::Header {
+                plugins,
+                ..man::Header::current()
+            };
+            let pages = man::generate_all(cmd, &header, dir)?;
+            Ok(pages.into_iter().map(|page| page.path).collect())
+        });
+    }
+    if let Some(dir) = args.markdown {
+        let plugins = doc_plugins(ctx, strict)?;
+        return write_all(dir, |cmd, dir| markdown::generate_all(cmd, &plugins, dir));
+    }
+    if let Some(dir) = args.book {
+        let plugins = doc_plugins(ctx, strict)?;
+        return write_all(dir, |cmd, dir| markdown::generate_book(cmd, &plugins, dir));
+    }
+    if args.install {
+        return install(&args, ctx.lang);
+    }
+
+    let path = command_path(&args.command)?;
+    if args.path {
+        // Pages rendered on demand only exist in memory, so only an
+        // installed one has a location to print.
+        let page = installed_page(&path, ctx.lang)
+            .ok_or_else(|| AppError::NotInstalled(man::file_name(&path)))?;
+        return Ok(Output::Text(format!("{}\n", page.display())));
+    }
+
+    // The page this binary renders itself matches it exactly, unlike an
+    // installed copy that may come from another version, and needs no man
+    // directory at all.
+    let cmd = i18n::localize(Cli::command(), ctx.lang);
+    let page = man::render_path(cmd, &path, &man::Header::current())
+        .expect("command_path only returns existing commands");
+    show(&page, ctx)
+}
+
+// Show a rendered page with `man -l -`, or return it as plain text where man
+// is not installed. man's status becomes ours.
+fn show(page: &[u8], ctx: &mut Context) -> Result<Output, AppError> {
+    // man pages for a person, and for anyone else writes plain text that we
+    // pass on.
+    let stdout = if ctx.interactive {
+        Stdio::inherit()
+    } else {
+        Stdio::piped()
+    };
+    let mut child = match process::Command::new("man")
+        .args(["-l", "-"])
+        .stdin(Stdio::piped())
+        .stdout(stdout)
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            let width = terminal_size::terminal_size()
+                .map_or(80, |(terminal_size::Width(w), _)| usize::from(w))
+                .min(80);
+            let text = man_text::render(&String::from_utf8_lossy(page), width);
+            return Ok(Output::Page(text));
+        }
+        Err(e) => return Err(AppError::ManViewer(e)),
+    };
+    let (stdin, rendered) = (child.stdin.take(), child.stdout.take());
+    thread::scope(|scope| {
+        // Fed from another thread so man never waits on a full pipe to us.
+        let feeder = scope.spawn(move || match stdin.map(|mut stdin| stdin.write_all(page)) {
+            // The user may quit man before it read everything.
+            Some(Err(e)) if e.kind() != io::ErrorKind::BrokenPipe => Err(AppError::ManViewer(e)),
+            _ => Ok(()),
+        });
+        if let Some(mut rendered) = rendered {
+            io::copy(&mut rendered, ctx.stdout).map_err(AppError::Output)?;
+        }
+        feeder.join().expect("the feeder does not panic")
+    })?;
+    let status = child.wait().map_err(AppError::ManViewer)?;
+    Ok(Output::Exited(status.code().unwrap_or(exit::FAILURE)))
+}
+
+// Copy (or gzip) freshly rendered pages into `<prefix>/man<section>` (man1,
+// man8 for the server page), and the translations into
+// `<prefix>/<lang>/man<section>`. What was written is listed in `lang`.
+fn install(args: &DocsCmd, lang: Lang) -> Result<Output, AppError> {
+    let prefix = match (&args.prefix, args.user) {
+        (Some(prefix), _) => prefix.clone(),
+        (None, true) => paths::expand(paths::USER_MAN_PREFIX).ok_or_else(|| AppError::Install {
+            dir: PathBuf::from(paths::USER_MAN_PREFIX),
+            source: io::Error::new(io::ErrorKind::NotFound, "HOME is not set"),
+        })?,
+        (None, false) => PathBuf::from(paths::INSTALL_MAN_PREFIX),
+    };
+    let mut lines = Vec::new();
+    for page_lang in Lang::ALL {
+        // Rendered in memory, so nothing but the installed files is written.
+        let cmd = i18n::localize(Cli::command(), page_lang);
+        let pages =
+            man::render_all(cmd, &man::Header::current()).expect("pages render into memory");
+        for page in pages {
+            let mut dest = prefix.clone();
+            dest.extend(man::lang_dir(page_lang));
+            dest.push(format!("man{}", page.section));
+            let failed = |source| AppError::Install {
+                dir: dest.clone(),
+                source,
+            };
+            let mut target = dest.join(&page.file_name);
+            if args.gzip {
+                target.as_mut_os_string().push(".gz");
+            }
+            if !args.dry_run {
+                let mut contents = page.contents;
+                if args.gzip {
+                    contents = man::gzip(&contents, 0).map_err(failed)?;
+                }
+                fs::create_dir_all(&dest)
+                    .and_then(|()| fs::write(&target, contents))
+                    .map_err(failed)?;
+            }
+            let key = if args.dry_run {
+                "msg.docs.would_install"
+            } else {
+                "msg.docs.installed"
+            };
+            lines.push(i18n::trf(lang, key, &[("path", &target.display())]));
+        }
+    }
+
+    if !args.dry_run {
+        if let Some(program) = update_index(&prefix) {
+            lines.push(i18n::trf(
+                lang,
+                "msg.docs.indexed",
+                &[("program", &program)],
+            ));
+        }
+    }
+    Ok(Output::Info(lines))
+}
+
+// Refresh the apropos/whatis database of `prefix` with man-db's `mandb` or
+// BSD's `makewhatis`, whichever exists. The pages work without it, so a
+// failing indexer is not an error. Returns the program that succeeded.
+fn update_index(prefix: &Path) -> Option<&'static str> {
+    ["mandb", "makewhatis"].into_iter().find(|program| {
+        process::Command::new(program)
+            .arg(prefix)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    })
+}
+
+// `--generate` and `--markdown` write English, like the top level of the
+// `cargo xtask mangen` output.
+fn english() -> clap::Command {
+    i18n::localize(Cli::command(), Lang::En)
+}
+
+// The plugins of MYCLI_DOC_PLUGINS, with a warning for each one that has no
+// description to show.
+fn doc_plugins(ctx: &mut Context, strict: bool) -> Result<Vec<plugins::Documented>, AppError> {
+    let documented = plugins::documented(&ctx.plugin_path, false);
+    for plugin in documented.iter().filter(|p| p.description.is_none()) {
+        let message = i18n::trf(
+            ctx.lang,
+            "msg.docs.undescribed_plugin",
+            &[("name", &plugin.name)],
+        );
+        ui::warn(ctx, strict, message)?;
+    }
+    Ok(documented)
+}
+
+// Create `dir` and fill it with `generate`, listing what was written.
+fn write_all(
+    dir: PathBuf,
+    generate: impl FnOnce(clap::Command, &Path) -> io::Result<Vec<PathBuf>>,
+) -> Result<Output, AppError> {
+    let written = fs::create_dir_all(&dir)
+        .and_then(|()| generate(english(), &dir))
+        .map_err(|source| AppError::Docs { dir, source })?;
+    Ok(Output::Info(
+        written
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect(),
+    ))
+}
+
+// Resolve `config.set` / `config set` to `["mycli", "config", "set"]`,
+// checking that every part names a real subcommand.
+fn command_path(words: &[String]) -> Result<Vec<String>, AppError> {
+    let root = Cli::command();
+    let mut path = vec![root.get_name().to_owned()];
+    let mut cmd = &root;
+    for part in words
+        .iter()
+        .flat_map(|w| w.split('.'))
+        .filter(|p| !p.is_empty())
+    {
+        cmd = cmd
+            .find_subcommand(part)
+            .filter(|sub| !sub.is_hide_set())
+            .ok_or_else(|| AppError::UnknownCommand(words.join(" ")))?;
+        path.push(cmd.get_name().to_owned());
+    }
+    Ok(path)
+}
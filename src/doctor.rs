@@ -0,0 +1,292 @@
+// `mycli doctor`: environment diagnostics.
+//
+// Each check is an entry in `CHECKS`; add a function returning an `Outcome`
+// and register it there to extend the report.
+
+use std::env;
+use std::io::{self, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+
+use clap::CommandFactory;
+use serde_json::{json, Value};
+
+use crate::cli::{Cli, ReportFormat};
+#[cfg(feature = "docs-gen")]
+use crate::docs;
+use crate::error::AppError;
+use crate::i18n;
+#[cfg(feature = "docs-gen")]
+use crate::man;
+use crate::output::Style;
+use crate::plugins;
+use crate::Context;
+
+/// Result level of a single check.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    fn label(self) -> &'static str {
+        match self {
+            Status::Pass => "PASS",
+            Status::Warn => "WARN",
+            Status::Fail => "FAIL",
+        }
+    }
+}
+
+/// What a check found, with a one-line explanation.
+#[derive(Debug)]
+pub struct Outcome {
+    pub status: Status,
+    pub detail: String,
+}
+
+impl Outcome {
+    fn new(status: Status, detail: impl Into<String>) -> Self {
+        Outcome {
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// A named diagnostic of the environment of a `Context`.
+pub struct Check {
+    pub name: &'static str,
+    pub run: fn(&Context) -> Outcome,
+}
+
+/// Every check `mycli doctor` runs, in report order.
+pub const CHECKS: &[Check] = &[
+    Check {
+        name: "editor",
+        run: check_editor,
+    },
+    #[cfg(feature = "docs-gen")]
+    Check {
+        name: "man-pages",
+        run: check_man_pages,
+    },
+    Check {
+        name: "server-port",
+        run: check_server_port,
+    },
+    Check {
+        name: "plugins",
+        run: check_plugins,
+    },
+];
+
+/// The outcome of every check, in report order.
+#[derive(Debug)]
+pub struct Report {
+    pub format: ReportFormat,
+    pub results: Vec<(&'static str, Outcome)>,
+    /// Whether warnings count as failures (`--strict`).
+    pub strict: bool,
+}
+
+impl Report {
+    /// Checks that failed, or with `strict` warned.
+    pub fn failed(&self) -> usize {
+        let failed = self.count(Status::Fail);
+        if self.strict {
+            failed + self.count(Status::Warn)
+        } else {
+            failed
+        }
+    }
+
+    /// Write the report to `out` in its format. There is nothing left to
+    /// write for ndjson, which [`run`] streamed.
+    pub fn render(&self, out: &mut dyn Write, style: &Style) -> io::Result<()> {
+        let (passed, warned, failed) = (
+            self.count(Status::Pass),
+            self.count(Status::Warn),
+            self.count(Status::Fail),
+        );
+        match self.format {
+            ReportFormat::Plain => {
+                let width = self
+                    .results
+                    .iter()
+                    .map(|(name, _)| name.len())
+                    .max()
+                    .unwrap_or(0);
+                for (name, outcome) in &self.results {
+                    writeln!(
+                        out,
+                        "{}  {name:<width$}  {}",
+                        outcome.status.label(),
+                        outcome.detail
+                    )?;
+                }
+                let summary = i18n::trf(
+                    style.lang,
+                    "msg.doctor.summary",
+                    &[
+                        ("passed", &passed),
+                        ("warned", &warned),
+                        ("failed", &failed),
+                    ],
+                );
+                writeln!(out, "\n{summary}")
+            }
+            ReportFormat::Json => {
+                let checks: Vec<_> = self
+                    .results
+                    .iter()
+                    .map(|(name, outcome)| record(name, outcome))
+                    .collect();
+                let report = json!({
+                    "checks": checks,
+                    "summary": { "pass": passed, "warn": warned, "fail": failed },
+                });
+                writeln!(out, "{}", style.json(&report))
+            }
+            ReportFormat::Ndjson => Ok(()),
+        }
+    }
+
+    fn count(&self, status: Status) -> usize {
+        self.results
+            .iter()
+            .filter(|(_, o)| o.status == status)
+            .count()
+    }
+}
+
+/// Run all checks against `ctx`. With `--format ndjson` each one is written
+/// to its stdout as it is done; the other formats are left to
+/// [`Report::render`].
+pub fn run(
+    format: ReportFormat,
+    strict: bool,
+    style: &Style,
+    ctx: &mut Context,
+) -> Result<Report, AppError> {
+    let mut results = Vec::new();
+    for check in CHECKS {
+        let outcome = (check.run)(ctx);
+        // Streamed so a consumer sees each check as soon as it is done.
+        if format == ReportFormat::Ndjson {
+            writeln!(
+                ctx.stdout,
+                "{}",
+                style.json_line(&record(check.name, &outcome))
+            )
+            .and_then(|()| ctx.stdout.flush())
+            .map_err(AppError::Output)?;
+        }
+        results.push((check.name, outcome));
+    }
+    Ok(Report {
+        format,
+        results,
+        strict,
+    })
+}
+
+// One check in the JSON formats.
+fn record(name: &str, outcome: &Outcome) -> Value {
+    json!({
+        "name": name,
+        "status": outcome.status.label().to_lowercase(),
+        "detail": outcome.detail,
+    })
+}
+
+fn check_editor(_: &Context) -> Outcome {
+    let Some((var, editor)) = ["VISUAL", "EDITOR"].iter().find_map(|var| {
+        env::var(var)
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .map(|v| (var, v))
+    }) else {
+        return Outcome::new(Status::Warn, "neither VISUAL nor EDITOR is set");
+    };
+
+    let program = editor.split_whitespace().next().unwrap_or_default();
+    match find_program(program) {
+        Some(path) => Outcome::new(Status::Pass, format!("{var}={editor} ({})", path.display())),
+        None => Outcome::new(
+            Status::Fail,
+            format!("{var}={editor} is not an executable on PATH"),
+        ),
+    }
+}
+
+#[cfg(feature = "docs-gen")]
+fn check_man_pages(ctx: &Context) -> Outcome {
+    let file = man::file_name(&["mycli"]);
+    match docs::installed_page(&["mycli"], ctx.lang) {
+        Some(path) => Outcome::new(Status::Pass, format!("found {}", path.display())),
+        None => Outcome::new(
+            Status::Warn,
+            format!("{file} is not installed; open the generated pages with `mycli docs`"),
+        ),
+    }
+}
+
+fn check_server_port(_: &Context) -> Outcome {
+    // Use the defaults declared in src/cli.rs so this check follows them.
+    let cmd = Cli::command();
+    let server = cmd
+        .find_subcommand("server")
+        .expect("server subcommand exists");
+    let default = |id: &str| {
+        server
+            .get_arguments()
+            .find(|arg| arg.get_id() == id)
+            .and_then(|arg| arg.get_default_values().first())
+            .map(|value| value.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+    let addr = format!("{}:{}", default("addr"), default("port"));
+
+    match TcpListener::bind(&addr) {
+        Ok(_) => Outcome::new(Status::Pass, format!("default address {addr} is free")),
+        Err(e) => Outcome::new(
+            Status::Warn,
+            format!("default address {addr} is unavailable: {e}"),
+        ),
+    }
+}
+
+fn check_plugins(ctx: &Context) -> Outcome {
+    let found = plugins::discover(&ctx.plugin_path);
+    let cmd = Cli::command();
+    let shadowed: Vec<&str> = found
+        .iter()
+        .map(|p| p.name.as_str())
+        .filter(|name| cmd.find_subcommand(name).is_some())
+        .collect();
+
+    if shadowed.is_empty() {
+        Outcome::new(Status::Pass, format!("{} plugin(s) found", found.len()))
+    } else {
+        Outcome::new(
+            Status::Warn,
+            format!("shadowed by built-in commands: {}", shadowed.join(", ")),
+        )
+    }
+}
+
+// Resolve a program the way a shell would: paths are checked as-is, bare
+// names are searched on PATH.
+fn find_program(program: &str) -> Option<PathBuf> {
+    let path = Path::new(program);
+    if path.components().count() > 1 {
+        return path.is_file().then(|| path.to_owned());
+    }
+    let file = format!("{program}{}", env::consts::EXE_SUFFIX);
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(&file))
+        .find(|candidate| candidate.is_file())
+}
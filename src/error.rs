@@ -0,0 +1,291 @@
+// Error reporting shared by the CLI entry point.
+//
+// Every failure is reported either as a human sentence or, with
+// `--errors json`, as `{"error": {"code", "message", "hint"}}` on stderr.
+// The `code` strings are part of the interface and must not change.
+
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::process;
+
+use clap::error::ErrorKind;
+use serde_json::{json, Value};
+
+use crate::args;
+use crate::cli::ErrorFormat;
+use crate::exit;
+use crate::i18n::{self, Lang};
+
+/// Failures of the commands themselves (as opposed to usage errors).
+#[derive(Debug)]
+pub enum AppError {
+    /// Writing to stdout (or a pager) failed.
+    Output(io::Error),
+    /// A `mycli-<name>` plugin was found but could not be started.
+    Plugin {
+        name: String,
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// `doctor` found problems.
+    ChecksFailed { failed: usize },
+    /// `docs` was given a command path that does not exist.
+    UnknownCommand(String),
+    /// `docs --path` found no installed copy of the page.
+    NotInstalled(String),
+    /// `man` could not be started to show a page.
+    ManViewer(io::Error),
+    /// `docs --generate` could not write into the directory.
+    Docs { dir: PathBuf, source: io::Error },
+    /// `docs --install` could not write into the man directory.
+    Install { dir: PathBuf, source: io::Error },
+    /// `config get` found the key in neither scope.
+    KeyNotFound(String),
+    /// A config file could not be read or written.
+    Config { path: PathBuf, source: io::Error },
+    /// A warning under `--strict`.
+    Strict(String),
+    /// A usage error found by clap, or one clap could not check itself.
+    Usage(clap::Error),
+}
+
+impl AppError {
+    /// Stable machine-readable identifier of the error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Output(_) => "OUTPUT_FAILED",
+            AppError::Plugin { .. } => "PLUGIN_FAILED",
+            AppError::ChecksFailed { .. } => "CHECKS_FAILED",
+            AppError::UnknownCommand(_) => "UNKNOWN_COMMAND",
+            AppError::ManViewer(_) => "MAN_VIEWER_FAILED",
+            AppError::NotInstalled(_) => "DOCS_NOT_INSTALLED",
+            AppError::Docs { .. } => "DOCS_WRITE_FAILED",
+            AppError::Install { .. } => "DOCS_INSTALL_FAILED",
+            AppError::KeyNotFound(_) => "KEY_NOT_FOUND",
+            AppError::Config { .. } => "CONFIG_FAILED",
+            AppError::Strict(_) => "STRICT_WARNING",
+            AppError::Usage(err) => clap_code(err.kind()),
+        }
+    }
+
+    /// Human-readable description in `lang`.
+    pub fn message(&self, lang: Lang) -> String {
+        match self {
+            AppError::Output(e) => i18n::trf(lang, "error.output", &[("error", e)]),
+            AppError::Plugin { name, path, source } => i18n::trf(
+                lang,
+                "error.plugin",
+                &[("name", name), ("path", &path.display()), ("error", source)],
+            ),
+            AppError::ChecksFailed { failed } => {
+                i18n::trf(lang, "error.checks_failed", &[("failed", failed)])
+            }
+            AppError::UnknownCommand(name) => {
+                i18n::trf(lang, "error.unknown_command", &[("name", name)])
+            }
+            AppError::NotInstalled(page) => {
+                i18n::trf(lang, "error.not_installed", &[("page", page)])
+            }
+            AppError::ManViewer(e) => i18n::trf(lang, "error.man_viewer", &[("error", e)]),
+            AppError::Docs { dir, source } => i18n::trf(
+                lang,
+                "error.docs_write",
+                &[("dir", &dir.display()), ("error", source)],
+            ),
+            AppError::Install { dir, source } => i18n::trf(
+                lang,
+                "error.docs_install",
+                &[("dir", &dir.display()), ("error", source)],
+            ),
+            AppError::KeyNotFound(key) => i18n::trf(lang, "error.key_not_found", &[("key", key)]),
+            AppError::Config { path, source } => i18n::trf(
+                lang,
+                "error.config",
+                &[("path", &path.display()), ("error", source)],
+            ),
+            AppError::Strict(warning) => i18n::trf(lang, "error.strict", &[("warning", warning)]),
+            AppError::Usage(err)
+                if err.kind() == ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand =>
+            {
+                i18n::tr(lang, "error.missing_subcommand").to_owned()
+            }
+            AppError::Usage(err) => clap_message(&err.render().to_string()).0,
+        }
+    }
+
+    /// Suggestion printed along with the error, if any.
+    pub fn hint(&self, lang: Lang) -> Option<&'static str> {
+        match self {
+            AppError::Output(_) => Some(i18n::tr(lang, "error.output.hint")),
+            AppError::ManViewer(_) => Some(i18n::tr(lang, "error.man_viewer.hint")),
+            AppError::NotInstalled(_) => Some(i18n::tr(lang, "error.not_installed.hint")),
+            AppError::UnknownCommand(_) => Some(i18n::tr(lang, "error.unknown_command.hint")),
+            AppError::KeyNotFound(_) => Some(i18n::tr(lang, "error.key_not_found.hint")),
+            AppError::Strict(_) => Some(i18n::tr(lang, "error.strict.hint")),
+            AppError::Install { source, .. }
+                if source.kind() == io::ErrorKind::PermissionDenied =>
+            {
+                Some(i18n::tr(lang, "error.docs_install.hint"))
+            }
+            AppError::Plugin { .. }
+            | AppError::Usage(_)
+            | AppError::ChecksFailed { .. }
+            | AppError::Docs { .. }
+            | AppError::Install { .. }
+            | AppError::Config { .. } => None,
+        }
+    }
+
+    /// Process exit code for this error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            AppError::UnknownCommand(_) => exit::USAGE,
+            AppError::Usage(err) => err.exit_code(),
+            _ => exit::FAILURE,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message(Lang::En))
+    }
+}
+
+/// The `--errors` format, looked up before parsing so clap's own errors can
+/// be reported in it too.
+pub fn early_format() -> ErrorFormat {
+    match args::early_value("errors").as_deref() {
+        Some("json") => ErrorFormat::Json,
+        _ => ErrorFormat::Human,
+    }
+}
+
+/// Render panics in the JSON shape with code `INTERNAL`.
+pub fn install_panic_hook(format: ErrorFormat) {
+    if format != ErrorFormat::Json {
+        return;
+    }
+    std::panic::set_hook(Box::new(|info| {
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panic".to_owned());
+        let message = match info.location() {
+            Some(loc) => format!("{message} ({}:{})", loc.file(), loc.line()),
+            None => message,
+        };
+        print_json(
+            "INTERNAL",
+            &message,
+            Some("this is a bug, please report it"),
+        );
+    }));
+}
+
+/// Report an application error and exit.
+pub fn exit(err: AppError, format: ErrorFormat) -> ! {
+    // Usage errors keep clap's layout, with the usage line and tips.
+    let err = match err {
+        AppError::Usage(err) => exit_clap(err, format),
+        err => err,
+    };
+    // JSON reports are meant for programs and always stay in English.
+    match format {
+        ErrorFormat::Human => {
+            let lang = Lang::from_env();
+            let prefix = i18n::tr(lang, "error.prefix");
+            eprintln!("{prefix}: {}", err.message(lang));
+            if let Some(hint) = err.hint(lang) {
+                eprintln!("\n  {}: {hint}", i18n::tr(lang, "error.tip"));
+            }
+        }
+        ErrorFormat::Json => print_json(err.code(), &err.to_string(), err.hint(Lang::En)),
+    }
+    process::exit(err.exit_code());
+}
+
+/// Report a clap error and exit with clap's exit code.
+///
+/// Help and version "errors" are always printed as-is.
+pub fn exit_clap(err: clap::Error, format: ErrorFormat) -> ! {
+    if format == ErrorFormat::Human || !err.use_stderr() {
+        err.exit();
+    }
+    eprintln!("{}", usage_report(&err));
+    process::exit(err.exit_code());
+}
+
+// The JSON report of a usage error: `code`, `message` and `hint` like every
+// other error. When clap shows the help because a subcommand is missing,
+// that help comes along in an extra `help` field.
+fn usage_report(err: &clap::Error) -> Value {
+    let rendered = err.render().to_string();
+    let (message, hint, help) = match err.kind() {
+        ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand => (
+            i18n::tr(Lang::En, "error.missing_subcommand").to_owned(),
+            None,
+            Some(rendered.as_str()),
+        ),
+        _ => {
+            let (message, hint) = clap_message(&rendered);
+            (message, hint, None)
+        }
+    };
+    let mut report = json!({ "error": {
+        "code": clap_code(err.kind()),
+        "message": message,
+        "hint": hint,
+    } });
+    if let Some(help) = help {
+        report["error"]["help"] = help.into();
+    }
+    report
+}
+
+// The sentence and tip of a rendered clap error, without the usage and
+// "For more information" lines. Indented lines under a sentence ending in
+// `:` (the missing arguments, say) are appended to it as a list.
+fn clap_message(rendered: &str) -> (String, Option<&str>) {
+    let mut message = String::new();
+    let mut hint = None;
+    for line in rendered.lines().map(str::trim) {
+        if line.starts_with("Usage:") || line.starts_with("For more information") {
+            break;
+        }
+        if let Some(tip) = line.strip_prefix("tip: ") {
+            hint = Some(tip);
+        } else if !line.is_empty() {
+            if !message.is_empty() {
+                message.push_str(if message.ends_with(':') { " " } else { ", " });
+            }
+            message.push_str(line.strip_prefix("error: ").unwrap_or(line));
+        }
+    }
+    (message, hint)
+}
+
+fn clap_code(kind: ErrorKind) -> &'static str {
+    match kind {
+        ErrorKind::InvalidValue | ErrorKind::ValueValidation => "INVALID_VALUE",
+        ErrorKind::UnknownArgument => "UNKNOWN_ARGUMENT",
+        ErrorKind::InvalidSubcommand => "UNKNOWN_COMMAND",
+        ErrorKind::MissingRequiredArgument => "MISSING_ARGUMENT",
+        ErrorKind::MissingSubcommand | ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand => {
+            "MISSING_COMMAND"
+        }
+        ErrorKind::ArgumentConflict => "ARGUMENT_CONFLICT",
+        ErrorKind::TooManyValues | ErrorKind::TooFewValues | ErrorKind::WrongNumberOfValues => {
+            "WRONG_NUMBER_OF_VALUES"
+        }
+        _ => "USAGE",
+    }
+}
+
+fn print_json(code: &str, message: &str, hint: Option<&str>) {
+    let report = json!({ "error": { "code": code, "message": message, "hint": hint } });
+    eprintln!("{report}");
+}
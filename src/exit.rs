@@ -0,0 +1,92 @@
+// Exit status contract of mycli, also rendered as the EXIT STATUS section of
+// every man page (src/man.rs) and at the end of `--help-all`.
+
+/// The command succeeded.
+pub const SUCCESS: i32 = 0;
+/// The command ran but failed; stderr says why.
+pub const FAILURE: i32 = 1;
+/// The command line was invalid (clap uses the same code).
+pub const USAGE: i32 = 2;
+/// A panic, i.e. a bug (Rust's default panic exit code).
+pub const INTERNAL: i32 = 101;
+
+/// A documented exit status. `code: None` stands for any status not listed,
+/// e.g. one passed through from another program.
+pub struct Status {
+    pub code: Option<i32>,
+    pub meaning: &'static str,
+}
+
+/// Statuses every command can return.
+pub const COMMON: &[Status] = &[
+    Status {
+        code: Some(SUCCESS),
+        meaning: "Success.",
+    },
+    Status {
+        code: Some(FAILURE),
+        meaning: "The command failed; the error on stderr says why.",
+    },
+    Status {
+        code: Some(USAGE),
+        meaning: "Invalid usage: unknown command or argument, missing or invalid value.",
+    },
+    Status {
+        code: Some(INTERNAL),
+        meaning: "Internal error. This is a bug, please report it.",
+    },
+];
+
+// Command-specific meanings, keyed by command path (`mycli doctor`).
+const SPECIFIC: &[(&str, Status)] = &[
+    (
+        "mycli",
+        Status {
+            code: None,
+            meaning: "The exit status of a `mycli-<name>` plugin is passed through.",
+        },
+    ),
+    (
+        "mycli doctor",
+        Status {
+            code: Some(FAILURE),
+            meaning: "At least one check failed, or with --strict warned.",
+        },
+    ),
+    (
+        "mycli docs",
+        Status {
+            code: Some(USAGE),
+            meaning: "The given command does not exist.",
+        },
+    ),
+    (
+        "mycli docs",
+        Status {
+            code: None,
+            meaning: "man(1) failed; its exit status is passed through.",
+        },
+    ),
+];
+
+/// Statuses with a meaning specific to the command at `path`, e.g. `["mycli", "doctor"]`.
+pub fn specific<S: AsRef<str>>(path: &[S]) -> impl Iterator<Item = &'static Status> {
+    let path = path.iter().map(AsRef::as_ref).collect::<Vec<_>>().join(" ");
+    SPECIFIC
+        .iter()
+        .filter(move |(command, _)| *command == path)
+        .map(|(_, status)| status)
+}
+
+/// Every status of the command at `path`: the common ones, then its own.
+pub fn statuses<S: AsRef<str>>(path: &[S]) -> Vec<&'static Status> {
+    COMMON.iter().chain(specific(path)).collect()
+}
+
+impl Status {
+    /// The code as shown in documentation; unlisted codes read "other".
+    pub fn label(&self) -> String {
+        self.code
+            .map_or_else(|| "other".to_owned(), |code| code.to_string())
+    }
+}
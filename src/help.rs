@@ -0,0 +1,155 @@
+// Runtime help helpers built on top of the shared CLI definition.
+
+use clap::{Command, CommandFactory};
+
+use crate::args;
+use crate::cli::Cli;
+use crate::exit;
+use crate::i18n::{self, Lang};
+
+// clap's own fallback when the terminal width cannot be determined.
+const DEFAULT_WIDTH: usize = 100;
+
+/// Build the root command in `lang`, with help wrapping configured.
+///
+/// Help is rendered while parsing, so `--help-width`/`MYCLI_HELP_WIDTH` have to
+/// be looked up before clap sees the arguments. Without either, clap follows
+/// the terminal width.
+pub fn command(lang: Lang) -> Command {
+    let requested = requested_width();
+    let mut cmd = i18n::localize(Cli::command(), lang);
+    if let Some(width) = requested {
+        cmd = cmd.term_width(width);
+    }
+
+    // clap wraps line by line without hanging indents, so reflow the
+    // hand-formatted long description ourselves at the effective width.
+    let width = requested.or_else(terminal_width).unwrap_or(DEFAULT_WIDTH);
+    match cmd
+        .get_long_about()
+        .map(|about| reflow(&about.to_string(), width))
+    {
+        Some(about) => cmd.long_about(about),
+        None => cmd,
+    }
+}
+
+/// Render the long help of the root command followed by every (non-hidden)
+/// subcommand, depth-first, so `--help-all` shows the whole tree at once.
+/// Exit statuses are listed like in the man pages: the common ones at the
+/// end, command-specific ones after that command's help.
+pub fn help_all(lang: Lang) -> String {
+    let mut cmd = command(lang);
+    cmd.build();
+
+    let mut out = String::new();
+    let mut path = Vec::new();
+    render(&mut cmd, &mut path, &mut out);
+    out.push_str("\n--- exit status ---\n\n");
+    out.push_str(&exit_status(exit::COMMON.iter()));
+    out
+}
+
+fn render(cmd: &mut Command, path: &mut Vec<String>, out: &mut String) {
+    path.push(cmd.get_name().to_owned());
+    if !out.is_empty() {
+        let name = cmd
+            .get_bin_name()
+            .unwrap_or_else(|| cmd.get_name())
+            .to_owned();
+        out.push_str(&format!("\n--- {name} ---\n\n"));
+    }
+    out.push_str(&cmd.render_long_help().to_string());
+    let specific: Vec<_> = exit::specific(path).collect();
+    if !specific.is_empty() {
+        out.push_str("\nExit status:\n");
+        out.push_str(&exit_status(specific.into_iter()));
+    }
+
+    for sub in cmd
+        .get_subcommands_mut()
+        .filter(|s| !s.is_hide_set() && s.get_name() != "help")
+    {
+        render(sub, path, out);
+    }
+    path.pop();
+}
+
+fn exit_status<'a>(statuses: impl Iterator<Item = &'a exit::Status>) -> String {
+    statuses
+        .map(|status| format!("  {:<5}  {}\n", status.label(), status.meaning))
+        .collect()
+}
+
+// `--help-width`, falling back to `MYCLI_HELP_WIDTH`. Invalid values are
+// left for clap to report.
+fn requested_width() -> Option<usize> {
+    args::early_value("help-width")
+        .or_else(|| std::env::var("MYCLI_HELP_WIDTH").ok())?
+        .parse()
+        .ok()
+}
+
+fn terminal_width() -> Option<usize> {
+    terminal_size::terminal_size().map(|(terminal_size::Width(w), _)| usize::from(w))
+}
+
+// Greedy word wrap that keeps each line's indentation and gives `- ` list
+// items a hanging indent. A width of 0 means "do not wrap".
+fn reflow(text: &str, width: usize) -> String {
+    if width == 0 {
+        return text.to_owned();
+    }
+
+    let mut out = String::new();
+    for line in text.split('\n') {
+        let content = line.trim_start();
+        let indent = line.len() - content.len();
+        let hanging = if content.starts_with("- ") {
+            indent + 2
+        } else {
+            indent
+        };
+
+        let mut current = " ".repeat(indent);
+        let mut empty = true;
+        for word in content.split_whitespace() {
+            if !empty && current.len() + 1 + word.len() > width {
+                out.push_str(&current);
+                out.push('\n');
+                current = " ".repeat(hanging);
+                empty = true;
+            }
+            if !empty {
+                current.push(' ');
+            }
+            current.push_str(word);
+            empty = false;
+        }
+        out.push_str(current.trim_end());
+        out.push('\n');
+    }
+    out.pop();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reflow_wraps_with_indent_and_hanging_list_items() {
+        let text = "Intro text that wraps\n    - a list item that also wraps\n\nEnd";
+        assert_eq!(
+            reflow(text, 20),
+            "Intro text that\nwraps\n    - a list item\n      that also\n      wraps\n\nEnd"
+        );
+    }
+
+    #[test]
+    fn reflow_keeps_long_words_and_width_zero() {
+        assert_eq!(reflow("a unbreakableword b", 5), "a\nunbreakableword\nb");
+        let text = "  ragged   spacing stays\n";
+        assert_eq!(reflow(text, 0), text);
+    }
+}
@@ -0,0 +1,491 @@
+// Message catalog for localized help text and runtime messages.
+//
+// English help text lives in the clap derive attributes of src/cli.rs; other
+// locales override it by key. Runtime messages are looked up by key in the
+// active locale and fall back to English when a key or locale is missing.
+//
+// Help keys are the command path plus a suffix:
+//     mycli.config.set.about        about text of `mycli config set`
+//     mycli.config.set.long_about   long about text
+//     mycli.config.set.after_help   examples appended to the help
+//     mycli.config.set.arg.global   help of the argument with id `global`
+//     mycli.arg.strict.long_help    its `--help` text, where it has one
+
+use clap::Command;
+
+/// Languages with a catalog.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Lang {
+    /// English (default and fallback)
+    #[default]
+    En,
+    /// German
+    De,
+}
+
+impl Lang {
+    /// Every language with a catalog, English first.
+    pub const ALL: [Lang; 2] = [Lang::En, Lang::De];
+
+    /// ISO 639-1 code, as used in locale names and man page directories.
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::De => "de",
+        }
+    }
+
+    /// Language selected by `MYCLI_LANG`, then the usual locale variables
+    /// (`LC_ALL`, `LC_MESSAGES`, `LANG`). Unknown languages fall back to English.
+    pub fn from_env() -> Lang {
+        ["MYCLI_LANG", "LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .map_or(Lang::En, |value| Lang::parse(&value))
+    }
+
+    /// Parse a locale such as `de`, `de_DE.UTF-8` or `de-AT`.
+    pub fn parse(locale: &str) -> Lang {
+        let language = locale
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default();
+        match language.to_ascii_lowercase().as_str() {
+            "de" => Lang::De,
+            _ => Lang::En,
+        }
+    }
+
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Lang::En => EN,
+            Lang::De => DE,
+        }
+    }
+}
+
+/// Look up `key` in `lang` only, without falling back to English.
+pub fn lookup(lang: Lang, key: &str) -> Option<&'static str> {
+    lang.catalog()
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, text)| *text)
+}
+
+/// Translate a runtime message, falling back to English and then to the key.
+pub fn tr(lang: Lang, key: &'static str) -> &'static str {
+    lookup(lang, key)
+        .or_else(|| lookup(Lang::En, key))
+        .unwrap_or(key)
+}
+
+/// Translate a runtime message and fill in its `{name}` placeholders.
+pub fn trf(lang: Lang, key: &'static str, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+    args.iter()
+        .fold(tr(lang, key).to_owned(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), &value.to_string())
+        })
+}
+
+/// Replace the about/help strings of `cmd` and all its subcommands with the
+/// translations for `lang`. Strings without a translation keep their English
+/// text. Call this before the command is built so global args are localized
+/// before clap propagates them.
+pub fn localize(cmd: Command, lang: Lang) -> Command {
+    let path = cmd.get_name().to_owned();
+    localize_at(cmd, lang, &path)
+}
+
+fn localize_at(mut cmd: Command, lang: Lang, path: &str) -> Command {
+    let text = |suffix: &str| lookup(lang, &format!("{path}.{suffix}"));
+
+    if let Some(about) = text("about") {
+        cmd = cmd.about(about);
+    }
+    if let Some(long_about) = text("long_about") {
+        cmd = cmd.long_about(long_about);
+    }
+    if let Some(after_help) = text("after_help") {
+        cmd = cmd.after_help(after_help);
+    }
+
+    let ids: Vec<String> = cmd
+        .get_arguments()
+        .map(|arg| arg.get_id().to_string())
+        .collect();
+    for id in ids {
+        let help = text(&format!("arg.{id}"));
+        let long_help = text(&format!("arg.{id}.long_help"));
+        // An untranslated long help stays English rather than disappearing.
+        cmd = cmd.mut_arg(id, |arg| match (help, long_help) {
+            (Some(help), Some(long_help)) => arg.help(help).long_help(long_help),
+            (Some(help), None) => arg.help(help),
+            (None, Some(long_help)) => arg.long_help(long_help),
+            (None, None) => arg,
+        });
+    }
+
+    let names: Vec<String> = cmd
+        .get_subcommands()
+        .map(|sub| sub.get_name().to_owned())
+        .collect();
+    for name in names {
+        let sub_path = format!("{path}.{name}");
+        cmd = cmd.mut_subcommand(name, |sub| localize_at(sub, lang, &sub_path));
+    }
+    cmd
+}
+
+// English runtime messages. English help text is the source in src/cli.rs.
+const EN: &[(&str, &str)] = &[
+    (
+        "msg.server.start",
+        "server start on {addr}:{port} (verbosity: {verbose})",
+    ),
+    ("msg.remote.removed", "remote removed: {name}"),
+    ("msg.remote.added", "remote added: {name} -> {url}"),
+    ("msg.remote.info", "remote info requested: {name}"),
+    ("msg.config.set", "set {key} to {value} in {path}"),
+    ("msg.plugins.none", "no plugins found"),
+    ("msg.docs.installed", "installed {path}"),
+    ("msg.docs.would_install", "would install {path}"),
+    (
+        "msg.docs.indexed",
+        "updated the man page index with {program}",
+    ),
+    (
+        "msg.doctor.summary",
+        "{passed} passed, {warned} warnings, {failed} failed",
+    ),
+    (
+        "msg.docs.undescribed_plugin",
+        "plugin '{name}' from MYCLI_DOC_PLUGINS is not installed or does not answer --plugin-describe",
+    ),
+    ("warning.prefix", "warning"),
+    ("error.prefix", "error"),
+    ("error.tip", "tip"),
+    ("error.missing_subcommand", "a subcommand is required"),
+    (
+        "error.quiet_verbose",
+        "the argument '--verbose...' cannot be used with '--quiet'",
+    ),
+    ("error.output", "failed to write output: {error}"),
+    ("error.output.hint", "retry with --no-pager"),
+    ("error.checks_failed", "{failed} check(s) failed"),
+    ("error.unknown_command", "no such command: {name}"),
+    (
+        "error.unknown_command.hint",
+        "see `mycli --help-all` for every command",
+    ),
+    ("error.not_installed", "no installed man page {page} found"),
+    (
+        "error.not_installed.hint",
+        "install the pages with `mycli docs --install` (or `--install --user` for ~/.local/share/man)",
+    ),
+    ("error.man_viewer", "failed to run man: {error}"),
+    (
+        "error.man_viewer.hint",
+        "use --path to print the page location instead",
+    ),
+    (
+        "error.docs_write",
+        "failed to write man pages to {dir}: {error}",
+    ),
+    (
+        "error.docs_install",
+        "cannot install man pages into {dir}: {error}",
+    ),
+    (
+        "error.docs_install.hint",
+        "use --user to install into ~/.local/share/man, pick a writable --prefix, or run with sudo",
+    ),
+    (
+        "error.plugin",
+        "failed to run plugin '{name}' ({path}): {error}",
+    ),
+    ("error.key_not_found", "{key} is not set"),
+    (
+        "error.key_not_found.hint",
+        "set it with mycli config set, or --global for every directory",
+    ),
+    ("error.config", "failed to access {path}: {error}"),
+    ("error.strict", "{warning}"),
+    ("error.strict.hint", "without --strict this is only a warning"),
+];
+
+// The German `mycli.long_about`, mentioning `mycli docs` only where it exists
+// (see the English one in src/cli.rs).
+macro_rules! de_long_about {
+    ($install:literal, $docs:literal) => {
+        concat!(
+            r#"mycli ist eine kleine Beispiel-CLI, die automatisch erzeugte Manpages mit clap und clap_mangen zeigt.
+
+Sie demonstriert:
+    - Verschachtelte Unterbefehle (z. B. `config get`, `config set`)
+    - Ausführliche Hilfe- und Usage-Texte aus einer einzigen Quelle
+    - Erzeugte Manpages"#,
+            $install,
+            r#"
+
+Befehle der obersten Ebene:
+    - config: Konfigurationswerte verwalten (get/set)
+    - server: einen Demo-Server starten (Adresse/Port/Ausführlichkeit)
+    - remote: ein Remote per Name hinzufügen oder entfernen
+    - plugins: externe `mycli-<name>`-Plugins im PATH auflisten
+    - doctor: die Umgebung prüfen (Editor, Manpages, Port, Plugins)
+    - version: ausführliche Build-Informationen ausgeben
+"#,
+            $docs
+        )
+    };
+}
+
+#[cfg(feature = "docs-gen")]
+const DE_LONG_ABOUT: &str = de_long_about!(
+    ", installierbar mit `mycli docs --install`",
+    "    - docs: Manpages finden, anzeigen oder neu erzeugen\n"
+);
+#[cfg(not(feature = "docs-gen"))]
+const DE_LONG_ABOUT: &str = de_long_about!("", "");
+
+const DE: &[(&str, &str)] = &[
+    ("mycli.about", "Beispiel-CLI mit verschachtelten Unterbefehlen und Manpage-Erzeugung"),
+    ("mycli.long_about", DE_LONG_ABOUT),
+    ("mycli.arg.help_all", "Hilfe aller Befehle und Unterbefehle anzeigen"),
+    (
+        "mycli.arg.help_width",
+        "Hilfe nach COLS Spalten statt nach der Terminalbreite umbrechen (0 schaltet den Umbruch ab)",
+    ),
+    (
+        "mycli.arg.no_pager",
+        "Lange Ausgaben direkt statt über $MYCLI_PAGER/$PAGER ausgeben",
+    ),
+    (
+        "mycli.arg.quiet",
+        "Informative Ausgaben unterdrücken; Werte und Fehler werden weiterhin ausgegeben",
+    ),
+    (
+        "mycli.arg.pretty",
+        "Ausgaben von --format json eingerückt, ein Schlüssel pro Zeile",
+    ),
+    (
+        "mycli.arg.strict",
+        "Warnungen als Fehler behandeln und mit Status 1 beenden",
+    ),
+    (
+        "mycli.arg.strict.long_help",
+        "Warnungen als Fehler behandeln und mit Status 1 beenden. Betroffen sind genau:
+
+- doctor: Prüfungen mit WARN lassen den Lauf wie FAIL-Prüfungen scheitern.
+
+- docs --generate, --markdown und --book: ein in MYCLI_DOC_PLUGINS genanntes \
+Plugin, das nicht installiert ist oder nicht auf --plugin-describe antwortet, \
+bricht den Lauf ab, statt ohne Beschreibung aufgeführt zu werden.",
+    ),
+    (
+        "mycli.arg.color",
+        "JSON-Ausgaben hervorheben: im Terminal (auto), immer (always) oder nie (never)",
+    ),
+    ("mycli.arg.errors", "Fehler als lesbaren Text oder als JSON-Objekt melden"),
+    ("mycli.arg.version", "Version ausgeben"),
+    (
+        "mycli.arg.verbose",
+        "Mit --version auch die Cargo-Features ausgeben, mit denen das Programm gebaut wurde",
+    ),
+    ("mycli.config.about", "Konfigurationswerte verwalten"),
+    ("mycli.config.get.about", "Einen Konfigurationswert lesen"),
+    (
+        "mycli.config.get.arg.key",
+        "Zu lesender Konfigurationsschlüssel, z. B. \"core.editor\"",
+    ),
+    ("mycli.config.get.arg.format", "Ausgabeformat des Werts"),
+    ("mycli.config.set.about", "Einen Konfigurationswert setzen"),
+    (
+        "mycli.config.set.arg.key",
+        "Zu schreibender Konfigurationsschlüssel, z. B. \"core.editor\"",
+    ),
+    ("mycli.config.set.arg.value", "Wert, der dem Schlüssel zugewiesen wird"),
+    (
+        "mycli.config.set.arg.global",
+        "In den globalen Konfigurationsbereich schreiben",
+    ),
+    ("mycli.server.about", "Den Server starten"),
+    ("mycli.server.arg.port", "Port, auf dem gelauscht wird"),
+    ("mycli.server.arg.addr", "Bind-Adresse"),
+    ("mycli.server.arg.verbose", "Ausführlichere Ausgabe (-v, -vv); nicht zusammen mit --quiet"),
+    ("mycli.remote.about", "Mit Remotes arbeiten"),
+    ("mycli.remote.arg.name", "Name des Remotes"),
+    (
+        "mycli.remote.arg.url",
+        "URL des Remotes (z. B. https://example.com/repo.git)",
+    ),
+    (
+        "mycli.remote.arg.remove",
+        "Das Remote entfernen statt es hinzuzufügen",
+    ),
+    ("mycli.plugins.about", "Externe `mycli-<name>`-Plugins verwalten"),
+    (
+        "mycli.plugins.list.about",
+        "Gefundene Plugins und ihren Fundort auflisten",
+    ),
+    (
+        "mycli.plugins.list.arg.null",
+        "Ungepolsterte NAME<TAB>PFAD-Einträge ausgeben, jeder mit einem NUL-Byte statt eines Zeilenumbruchs abgeschlossen",
+    ),
+    ("mycli.doctor.about", "Häufige Probleme der Umgebung diagnostizieren"),
+    ("mycli.doctor.arg.format", "Format des Berichts"),
+    ("mycli.version.about", "Ausführliche Build-Informationen ausgeben"),
+    ("mycli.version.arg.format", "Ausgabeformat"),
+    ("mycli.docs.about", "Manpages finden, anzeigen oder neu erzeugen"),
+    (
+        "mycli.docs.arg.command",
+        "Befehl, dessen Seite gezeigt wird, z. B. \"config.set\" oder \"config set\"",
+    ),
+    ("mycli.docs.arg.path", "Nur den Pfad der Seite ausgeben"),
+    ("mycli.docs.arg.generate", "Alle Seiten nach DIR schreiben"),
+    (
+        "mycli.docs.arg.markdown",
+        "Eine Markdown-Referenz aller Befehle nach DIR schreiben",
+    ),
+    (
+        "mycli.docs.arg.book",
+        "Die Markdown-Referenz als mdBook-Quellbaum nach DIR schreiben",
+    ),
+    (
+        "mycli.docs.arg.install",
+        "Alle Seiten nach PREFIX/man1 installieren, die des Servers nach PREFIX/man8, und den Manpage-Index aktualisieren",
+    ),
+    (
+        "mycli.docs.arg.prefix",
+        "Manpage-Verzeichnis für die Installation (Standard: /usr/local/share/man)",
+    ),
+    ("mycli.docs.arg.user", "Stattdessen nach ~/.local/share/man installieren"),
+    ("mycli.docs.arg.gzip", "Gepackte Seiten installieren (mycli.1.gz, ...)"),
+    (
+        "mycli.docs.arg.dry_run",
+        "Nur ausgeben, was installiert würde, ohne etwas zu schreiben",
+    ),
+    (
+        "msg.server.start",
+        "Server gestartet auf {addr}:{port} (Ausführlichkeit: {verbose})",
+    ),
+    ("msg.remote.removed", "Remote entfernt: {name}"),
+    ("msg.remote.added", "Remote hinzugefügt: {name} -> {url}"),
+    ("msg.remote.info", "Remote-Informationen angefordert: {name}"),
+    ("msg.config.set", "{key} in {path} auf {value} gesetzt"),
+    ("msg.plugins.none", "keine Plugins gefunden"),
+    ("msg.docs.installed", "installiert: {path}"),
+    ("msg.docs.would_install", "würde installiert: {path}"),
+    ("msg.docs.indexed", "Manpage-Index mit {program} aktualisiert"),
+    (
+        "msg.doctor.summary",
+        "{passed} bestanden, {warned} Warnungen, {failed} fehlgeschlagen",
+    ),
+    (
+        "msg.docs.undescribed_plugin",
+        "Plugin '{name}' aus MYCLI_DOC_PLUGINS ist nicht installiert oder beantwortet --plugin-describe nicht",
+    ),
+    ("warning.prefix", "Warnung"),
+    ("error.prefix", "Fehler"),
+    ("error.tip", "Tipp"),
+    ("error.missing_subcommand", "ein Unterbefehl ist erforderlich"),
+    (
+        "error.quiet_verbose",
+        "das Argument '--verbose...' kann nicht zusammen mit '--quiet' verwendet werden",
+    ),
+    (
+        "error.output",
+        "Ausgabe konnte nicht geschrieben werden: {error}",
+    ),
+    ("error.output.hint", "mit --no-pager erneut versuchen"),
+    ("error.checks_failed", "{failed} Prüfung(en) fehlgeschlagen"),
+    ("error.unknown_command", "unbekannter Befehl: {name}"),
+    (
+        "error.unknown_command.hint",
+        "`mycli --help-all` zeigt alle Befehle",
+    ),
+    ("error.not_installed", "keine installierte Manpage {page} gefunden"),
+    (
+        "error.not_installed.hint",
+        "die Seiten mit `mycli docs --install` installieren (oder `--install --user` für ~/.local/share/man)",
+    ),
+    ("error.man_viewer", "man konnte nicht ausgeführt werden: {error}"),
+    (
+        "error.man_viewer.hint",
+        "mit --path stattdessen den Pfad der Seite ausgeben",
+    ),
+    (
+        "error.docs_write",
+        "Manpages konnten nicht nach {dir} geschrieben werden: {error}",
+    ),
+    (
+        "error.docs_install",
+        "Manpages können nicht nach {dir} installiert werden: {error}",
+    ),
+    (
+        "error.docs_install.hint",
+        "mit --user nach ~/.local/share/man installieren, ein beschreibbares --prefix wählen oder mit sudo ausführen",
+    ),
+    (
+        "error.plugin",
+        "Plugin '{name}' ({path}) konnte nicht ausgeführt werden: {error}",
+    ),
+    ("error.key_not_found", "{key} ist nicht gesetzt"),
+    (
+        "error.key_not_found.hint",
+        "mit mycli config set setzen, oder mit --global für alle Verzeichnisse",
+    ),
+    ("error.config", "Zugriff auf {path} fehlgeschlagen: {error}"),
+    ("error.strict", "{warning}"),
+    ("error.strict.hint", "ohne --strict ist dies nur eine Warnung"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_the_language_of_a_locale() {
+        for locale in ["de", "de_DE.UTF-8", "de-AT", "DE_ch", "de@euro"] {
+            assert_eq!(Lang::parse(locale), Lang::De, "{locale}");
+        }
+        for locale in ["", "C", "POSIX", "en_US.UTF-8", "fr_FR", "dex"] {
+            assert_eq!(Lang::parse(locale), Lang::En, "{locale}");
+        }
+    }
+
+    #[test]
+    fn long_help_is_translated_or_kept() {
+        // The German (help, long help) of a root flag with both texts.
+        let localized = |id: &'static str| {
+            let arg = clap::Arg::new(id).long(id).help("help").long_help("long");
+            let cmd = localize(Command::new("mycli").arg(arg), Lang::De);
+            let arg = cmd.get_arguments().next().unwrap();
+            (
+                arg.get_help().map(ToString::to_string),
+                arg.get_long_help().map(ToString::to_string),
+            )
+        };
+        let strict = localized("strict");
+        assert_eq!(strict.0.as_deref(), lookup(Lang::De, "mycli.arg.strict"));
+        assert_eq!(
+            strict.1.as_deref(),
+            lookup(Lang::De, "mycli.arg.strict.long_help")
+        );
+        let quiet = localized("quiet");
+        assert_eq!(quiet.0.as_deref(), lookup(Lang::De, "mycli.arg.quiet"));
+        assert_eq!(quiet.1.as_deref(), Some("long"));
+    }
+
+    #[test]
+    fn messages_fall_back_to_english_then_the_key() {
+        assert_eq!(tr(Lang::De, "error.prefix"), "Fehler");
+        assert_eq!(lookup(Lang::De, "no.such.key"), None);
+        assert_eq!(tr(Lang::De, "no.such.key"), "no.such.key");
+        assert_eq!(
+            trf(Lang::En, "error.checks_failed", &[("failed", &3)]),
+            "3 check(s) failed"
+        );
+    }
+}
@@ -0,0 +1,158 @@
+// Machine-readable description of the command tree for external tooling,
+// printed by `mycli --dump-cli-json` and written as mycli-cli.schema.json by
+// `cargo xtask mangen`.
+
+use std::any::TypeId;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use clap::{Arg, ArgAction, Command};
+use serde_json::{json, Value};
+
+/// Bump when the shape of the dump changes in a backwards-incompatible way.
+pub const INTROSPECTION_VERSION: u32 = 1;
+
+/// File name of the dump when written next to generated docs.
+pub const SCHEMA_FILE: &str = "mycli-cli.schema.json";
+
+/// The dump of `cmd` as pretty-printed JSON with a trailing newline, exactly
+/// as `--dump-cli-json` prints it.
+pub fn to_json(cmd: Command) -> String {
+    serde_json::to_string_pretty(&dump(cmd)).expect("JSON values serialize") + "\n"
+}
+
+/// Describe `cmd` and all of its subcommands as JSON.
+pub fn dump(mut cmd: Command) -> Value {
+    cmd.build();
+    json!({
+        "introspection_version": INTROSPECTION_VERSION,
+        "command": command(&cmd, &[]),
+    })
+}
+
+// `path` holds the subcommand names below the root, for the arg anchors.
+fn command(cmd: &Command, path: &[&str]) -> Value {
+    json!({
+        "name": cmd.get_name(),
+        "path": cmd.get_bin_name().unwrap_or_else(|| cmd.get_name()),
+        "version": cmd.get_version(),
+        "about": cmd.get_about().map(ToString::to_string),
+        "long_about": cmd.get_long_about().map(ToString::to_string),
+        "hidden": cmd.is_hide_set(),
+        "aliases": cmd.get_all_aliases().collect::<Vec<_>>(),
+        "args": cmd.get_arguments().map(|a| arg(a, path)).collect::<Vec<_>>(),
+        "subcommands": cmd
+            .get_subcommands()
+            .map(|sub| command(sub, &[path, &[sub.get_name()]].concat()))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn arg(arg: &Arg, path: &[&str]) -> Value {
+    let possible_values = arg
+        .get_possible_values()
+        .iter()
+        .map(|value| {
+            json!({
+                "name": value.get_name(),
+                "help": value.get_help().map(ToString::to_string),
+                "hidden": value.is_hide_set(),
+            })
+        })
+        .collect::<Vec<_>>();
+    let takes_value = arg.get_num_args().is_some_and(|n| n.takes_values());
+    let value_names = arg
+        .get_value_names()
+        .filter(|_| takes_value)
+        .map(|names| names.iter().map(ToString::to_string).collect::<Vec<_>>());
+
+    json!({
+        "id": arg.get_id().as_str(),
+        "anchor": anchor(arg, path),
+        "short": arg.get_short().map(String::from),
+        "long": arg.get_long(),
+        "positional": arg.is_positional(),
+        "required": arg.is_required_set(),
+        "global": arg.is_global_set(),
+        "hidden": arg.is_hide_set(),
+        "action": action(arg.get_action()),
+        "takes_value": takes_value,
+        "type": value_type(arg, takes_value),
+        "value_names": value_names,
+        "default_values": arg
+            .get_default_values()
+            .iter()
+            .map(|v| v.to_string_lossy().into_owned())
+            .collect::<Vec<_>>(),
+        "possible_values": possible_values,
+        "env": arg.get_env().map(|env| env.to_string_lossy().into_owned()),
+        "help": arg.get_help().map(ToString::to_string),
+        "long_help": arg.get_long_help().map(ToString::to_string),
+    })
+}
+
+// A name unique in the whole tree, for linking to the argument: the
+// subcommand path and the long flag (`config.set.--global`), else the short
+// one, else the id for positionals (`config.set.key`). Root arguments have
+// no path (`--quiet`).
+fn anchor(arg: &Arg, path: &[&str]) -> String {
+    let name = match (arg.get_long(), arg.get_short()) {
+        (Some(long), _) => format!("--{long}"),
+        (None, Some(short)) => format!("-{short}"),
+        (None, None) => arg.get_id().as_str().to_owned(),
+    };
+    [path, &[name.as_str()]].concat().join(".")
+}
+
+// `string`, `integer`, `boolean` or `enum` (see `possible_values`), from the
+// value parser; `other` for types we do not name.
+fn value_type(arg: &Arg, takes_value: bool) -> &'static str {
+    if !takes_value {
+        return match arg.get_action() {
+            ArgAction::Count => "integer",
+            _ => "boolean",
+        };
+    }
+    if !arg.get_possible_values().is_empty() {
+        return "enum";
+    }
+    let id = arg.get_value_parser().type_id();
+    let is = |types: &[TypeId]| types.iter().any(|t| id == *t);
+    if is(&[
+        TypeId::of::<String>(),
+        TypeId::of::<OsString>(),
+        TypeId::of::<PathBuf>(),
+    ]) {
+        "string"
+    } else if is(&[
+        TypeId::of::<u8>(),
+        TypeId::of::<u16>(),
+        TypeId::of::<u32>(),
+        TypeId::of::<u64>(),
+        TypeId::of::<usize>(),
+        TypeId::of::<i8>(),
+        TypeId::of::<i16>(),
+        TypeId::of::<i32>(),
+        TypeId::of::<i64>(),
+        TypeId::of::<isize>(),
+    ]) {
+        "integer"
+    } else if is(&[TypeId::of::<bool>()]) {
+        "boolean"
+    } else {
+        "other"
+    }
+}
+
+fn action(action: &ArgAction) -> &'static str {
+    match action {
+        ArgAction::Set => "set",
+        ArgAction::Append => "append",
+        ArgAction::SetTrue => "set_true",
+        ArgAction::SetFalse => "set_false",
+        ArgAction::Count => "count",
+        ArgAction::Help | ArgAction::HelpShort | ArgAction::HelpLong => "help",
+        ArgAction::Version => "version",
+        _ => "other",
+    }
+}
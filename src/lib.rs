@@ -0,0 +1,335 @@
+// Library target of mycli: the CLI definition, documentation generators and
+// command implementations. src/main.rs is a thin binary over it (parse, `run`,
+// render the `Output`, report the error or exit with the status), and the
+// `xtask` workspace member generates the man pages and completions from it.
+
+use std::ffi::OsString;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::{self, Stdio};
+
+use clap::error::ErrorKind;
+use clap::{CommandFactory, ValueEnum};
+use serde_json::json;
+
+mod args;
+pub mod cli;
+pub mod config;
+#[cfg(feature = "docs-gen")]
+mod docs;
+pub mod doctor;
+pub mod error;
+pub mod exit;
+pub mod help;
+pub mod i18n;
+pub mod introspect;
+#[cfg(feature = "docs-gen")]
+pub mod man;
+#[cfg(feature = "docs-gen")]
+mod man_text;
+#[cfg(feature = "docs-gen")]
+pub mod markdown;
+pub mod output;
+pub mod paths;
+pub mod plugins;
+mod ui;
+pub mod version;
+
+use error::AppError;
+use i18n::Lang;
+use output::Style;
+
+/// Where [`run`] writes and what it runs against: everything a command may
+/// learn about the process, so it can run in-process with another one.
+pub struct Context<'a> {
+    /// Output that programs stream themselves: plugins, man, ndjson records.
+    pub stdout: &'a mut dyn Write,
+    /// Warnings.
+    pub stderr: &'a mut dyn Write,
+    /// Whether stdout is a terminal: `--color auto` colors, long text is
+    /// paged, and plugins and man write to the terminal themselves rather
+    /// than through `stdout`.
+    pub interactive: bool,
+    /// Language of messages and pages.
+    pub lang: Lang,
+    /// Directory of the global configuration (see [`paths::config_root`]);
+    /// `None` where there is no home directory to put it in.
+    pub config_root: Option<PathBuf>,
+    /// Directory of the local configuration, normally the current one.
+    pub work_dir: PathBuf,
+    /// Directories searched for plugins (see [`plugins::search_path`]).
+    pub plugin_path: Vec<PathBuf>,
+}
+
+/// What a command that did not fail produced, before it is written out with
+/// [`Output::render`].
+#[derive(Debug)]
+pub enum Output {
+    /// Confirmations and progress for a person, one line each; `--quiet`
+    /// drops them.
+    Info(Vec<String>),
+    /// The result as text, written as it is.
+    Text(String),
+    /// The result as text, paged when it is long.
+    Page(String),
+    /// The value `config get` found, in its `--format`.
+    Value {
+        entry: config::Entry,
+        format: cli::OutputFormat,
+    },
+    /// The build information of `version`, in its `--format`.
+    Version {
+        info: version::BuildInfo,
+        format: cli::VersionFormat,
+    },
+    /// The checks `doctor` ran.
+    Report(doctor::Report),
+    /// The plugins `plugins list` found; with `null` as NUL-terminated
+    /// records.
+    Plugins {
+        found: Vec<plugins::Plugin>,
+        null: bool,
+    },
+    /// A plugin or man ran, wrote to the stdout of the `Context` itself and
+    /// exited with this status, which becomes ours.
+    Exited(i32),
+}
+
+impl Output {
+    /// Write the result to `out` as `style` asks.
+    pub fn render(&self, out: &mut dyn Write, style: &Style) -> Result<(), AppError> {
+        match self {
+            Output::Info(_) if style.quiet => Ok(()),
+            Output::Info(lines) => lines.iter().try_for_each(|line| writeln!(out, "{line}")),
+            Output::Text(text) => out.write_all(text.as_bytes()),
+            Output::Page(text) => style.page(out, text),
+            Output::Value { entry, format } => match format {
+                cli::OutputFormat::Plain => writeln!(out, "{}", entry.value),
+                cli::OutputFormat::Json => {
+                    let value = json!({
+                        "key": entry.key,
+                        "value": entry.value,
+                        "scope": entry.scope.to_string(),
+                    });
+                    writeln!(out, "{}", style.json(&value))
+                }
+            },
+            Output::Version { info, format } => writeln!(out, "{}", info.render(*format, style)),
+            Output::Report(report) => report.render(out, style),
+            Output::Plugins { found, null } => render_plugins(out, found, *null, style),
+            Output::Exited(_) => Ok(()),
+        }
+        .map_err(AppError::Output)
+    }
+
+    /// The exit status once the result is written, or the error to report
+    /// after it: failed `doctor` checks.
+    pub fn status(&self) -> Result<i32, AppError> {
+        match self {
+            Output::Report(report) => match report.failed() {
+                0 => Ok(exit::SUCCESS),
+                failed => Err(AppError::ChecksFailed { failed }),
+            },
+            Output::Exited(code) => Ok(*code),
+            _ => Ok(exit::SUCCESS),
+        }
+    }
+}
+
+/// Run the parsed command line against `ctx`. Errors, usage errors included,
+/// are returned for the caller to report; nothing here exits the process or
+/// reads its environment.
+pub fn run(opts: cli::Cli, ctx: &mut Context) -> Result<Output, AppError> {
+    let lang = ctx.lang;
+    let style = Style::new(&opts, ctx);
+    if opts.help_all {
+        return Ok(Output::Page(help::help_all(lang)));
+    }
+    if opts.version {
+        let line = version::BuildInfo::current().version_line(opts.verbose);
+        return Ok(Output::Text(format!("{line}\n")));
+    }
+    if opts.dump_cli_json {
+        return Ok(Output::Text(introspect::to_json(cli::Cli::command())));
+    }
+
+    let Some(command) = opts.command else {
+        // The error clap gives for a bare `mycli`: the root help, just as
+        // `mycli config` shows the help of config.
+        let err = help::command(lang)
+            .try_get_matches_from(["mycli"])
+            .expect_err("a bare mycli shows the help");
+        return Err(AppError::Usage(err));
+    };
+
+    // Checked here rather than with `conflicts_with`, which misses a global
+    // `-q` given before the subcommand.
+    if opts.quiet && matches!(&command, cli::Commands::Server(s) if s.verbose > 0) {
+        let mut cmd = cli::Cli::command();
+        cmd.build();
+        let server = cmd
+            .find_subcommand_mut("server")
+            .expect("server is a subcommand");
+        let err = server.error(
+            ErrorKind::ArgumentConflict,
+            i18n::tr(lang, "error.quiet_verbose"),
+        );
+        return Err(AppError::Usage(err));
+    }
+
+    let output = match command {
+        cli::Commands::External(args) => run_plugin(&args, &style, opts.errors, ctx)?,
+        cli::Commands::Server(s) => Output::Info(vec![i18n::trf(
+            lang,
+            "msg.server.start",
+            &[
+                ("addr", &s.addr),
+                ("port", &s.port),
+                ("verbose", &s.verbose),
+            ],
+        )]),
+        cli::Commands::Remote(r) => {
+            if r.remove {
+                Output::Info(vec![i18n::trf(
+                    lang,
+                    "msg.remote.removed",
+                    &[("name", &r.name)],
+                )])
+            } else if let Some(url) = r.url {
+                Output::Info(vec![i18n::trf(
+                    lang,
+                    "msg.remote.added",
+                    &[("name", &r.name), ("url", &url)],
+                )])
+            } else {
+                let msg = i18n::trf(lang, "msg.remote.info", &[("name", &r.name)]);
+                Output::Text(format!("{msg}\n"))
+            }
+        }
+        cli::Commands::Config(cfg) => match cfg.action {
+            cli::ConfigAction::Get(g) => Output::Value {
+                entry: config::get(ctx, &g.key)?,
+                format: g.format,
+            },
+            cli::ConfigAction::Set(s) => {
+                let scope = if s.global {
+                    config::Scope::Global
+                } else {
+                    config::Scope::Local
+                };
+                let entry = config::set(ctx, &s.key, &s.value, scope)?;
+                Output::Info(vec![i18n::trf(
+                    lang,
+                    "msg.config.set",
+                    &[
+                        ("key", &entry.key),
+                        ("value", &entry.value),
+                        ("path", &entry.path.display()),
+                    ],
+                )])
+            }
+        },
+        cli::Commands::Doctor(d) => {
+            Output::Report(doctor::run(d.format, opts.strict, &style, ctx)?)
+        }
+        #[cfg(feature = "docs-gen")]
+        cli::Commands::Docs(d) => docs::run(d, opts.strict, ctx)?,
+        cli::Commands::Version(v) => Output::Version {
+            info: version::BuildInfo::current(),
+            format: v.format,
+        },
+        cli::Commands::Plugins(p) => match p.action {
+            cli::PluginsAction::List(list) => Output::Plugins {
+                found: plugins::discover(&ctx.plugin_path),
+                null: list.null,
+            },
+        },
+    };
+    Ok(output)
+}
+
+// `plugins list`: aligned names and paths, or with `null` NUL-terminated
+// `<name>\t<path>` records.
+fn render_plugins(
+    out: &mut dyn Write,
+    found: &[plugins::Plugin],
+    null: bool,
+    style: &Style,
+) -> io::Result<()> {
+    if null {
+        // The path goes out as it is on disk, newlines and all.
+        for plugin in found {
+            out.write_all(plugin.name.as_bytes())?;
+            out.write_all(b"\t")?;
+            out.write_all(plugin.path.as_os_str().as_encoded_bytes())?;
+            out.write_all(b"\0")?;
+        }
+    } else {
+        let width = found.iter().map(|p| p.name.len()).max().unwrap_or(0);
+        for plugin in found {
+            writeln!(out, "{:<width$}  {}", plugin.name, plugin.path.display())?;
+        }
+    }
+    if found.is_empty() && !style.quiet {
+        writeln!(out, "{}", i18n::tr(style.lang, "msg.plugins.none"))?;
+    }
+    Ok(())
+}
+
+// An unknown subcommand `<name>` runs the `mycli-<name>` plugin with the
+// arguments that follow it (`args` is the name and those arguments), and its
+// exit status becomes ours. Without such a plugin it is clap's usage error.
+// The global output flags reach the plugin as MYCLI_QUIET, MYCLI_PRETTY
+// (`1`/`0`), MYCLI_COLOR (`always`/`never`, already resolved from `auto`)
+// and MYCLI_ERRORS (`human`/`json`).
+fn run_plugin(
+    args: &[OsString],
+    style: &Style,
+    format: cli::ErrorFormat,
+    ctx: &mut Context,
+) -> Result<Output, AppError> {
+    let (name, rest) = args.split_first().expect("clap passes the name first");
+    let Some(plugin) = name
+        .to_str()
+        .and_then(|name| plugins::find(&ctx.plugin_path, name))
+    else {
+        // clap's own error, with its "similar subcommand" tip, from a copy
+        // of the root that does not accept external subcommands.
+        let root = cli::Cli::command();
+        let err = clap::Command::new("mycli")
+            .args(root.get_arguments().cloned())
+            .subcommands(root.get_subcommands().cloned())
+            .try_get_matches_from([OsString::from("mycli"), name.clone()])
+            .expect_err("only known subcommands parse without external ones");
+        return Err(AppError::Usage(err));
+    };
+    let errors = format.to_possible_value().expect("no skipped variants");
+
+    let failed = |source| AppError::Plugin {
+        name: plugin.name.clone(),
+        path: plugin.path.clone(),
+        source,
+    };
+
+    // A plugin may be interactive itself, so on a terminal it gets the
+    // terminal; otherwise its output passes through `ctx.stdout`.
+    let stdout = if ctx.interactive {
+        Stdio::inherit()
+    } else {
+        Stdio::piped()
+    };
+    let mut child = process::Command::new(&plugin.path)
+        .args(rest)
+        .env("MYCLI_QUIET", if style.quiet { "1" } else { "0" })
+        .env("MYCLI_PRETTY", if style.pretty { "1" } else { "0" })
+        .env("MYCLI_COLOR", if style.color { "always" } else { "never" })
+        .env("MYCLI_ERRORS", errors.get_name())
+        .stdout(stdout)
+        .spawn()
+        .map_err(failed)?;
+    if let Some(mut out) = child.stdout.take() {
+        io::copy(&mut out, ctx.stdout).map_err(AppError::Output)?;
+    }
+    let status = child.wait().map_err(failed)?;
+    Ok(Output::Exited(status.code().unwrap_or(exit::FAILURE)))
+}
@@ -1,35 +1,41 @@
-use clap::Parser;
+use std::env;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use std::process;
 
-mod cli;
+use clap::FromArgMatches;
+
+use mycli::i18n::Lang;
+use mycli::output::Style;
+use mycli::{cli, error, help, paths, plugins, Context};
 
 fn main() {
-    let opts = cli::Cli::parse();
-    match opts.command {
-        cli::Commands::Server(s) => {
-            println!(
-                "server start on {}:{} (verbosity: {})",
-                s.addr, s.port, s.verbose
-            );
-        }
-        cli::Commands::Remote(r) => {
-            if r.remove {
-                println!("remote removed: {}", r.name);
-            } else if let Some(url) = r.url {
-                println!("remote added: {} -> {}", r.name, url);
-            } else {
-                println!("remote info requested: {}", r.name);
-            }
-        }
-        cli::Commands::Config(cfg) => match cfg.action {
-            cli::ConfigAction::Get(g) => {
-                println!("config get {} (format: {:?})", g.key, g.format);
-            }
-            cli::ConfigAction::Set(s) => {
-                println!(
-                    "config set {}={} (global: {})",
-                    s.key, s.value, s.global
-                );
-            }
-        },
+    let format = error::early_format();
+    error::install_panic_hook(format);
+    let lang = Lang::from_env();
+
+    let opts = help::command(lang)
+        .try_get_matches()
+        .and_then(|matches| cli::Cli::from_arg_matches(&matches))
+        .unwrap_or_else(|e| error::exit_clap(e, format));
+
+    let (mut stdout, mut stderr) = (io::stdout(), io::stderr());
+    let mut ctx = Context {
+        interactive: stdout.is_terminal(),
+        lang,
+        config_root: paths::config_root(),
+        work_dir: env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+        plugin_path: plugins::search_path(),
+        stdout: &mut stdout,
+        stderr: &mut stderr,
+    };
+    let style = Style::new(&opts, &ctx);
+    let status = mycli::run(opts, &mut ctx).and_then(|output| {
+        output.render(&mut stdout, &style)?;
+        output.status()
+    });
+    match status {
+        Ok(code) => process::exit(code),
+        Err(e) => error::exit(e, format),
     }
 }
@@ -0,0 +1,865 @@
+// Man page naming and generation, used by `mycli docs` and `cargo xtask mangen`.
+//
+// Pages are assembled from clap_mangen's section renderers so that extra
+// sections (EXIT STATUS, ENVIRONMENT, FILES, EXAMPLES, SEE ALSO, ...) can be
+// placed where man-pages(7) expects them.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use clap::{ArgAction, Command};
+use clap_mangen::Man;
+use roff::{bold, italic, roman, Roff};
+
+use crate::exit;
+use crate::i18n::Lang;
+use crate::paths;
+use crate::plugins;
+
+/// Manual section of every page not listed in `SECTIONS`.
+pub const SECTION: &str = "1";
+
+// Pages that belong to another section, by command path: daemons go to
+// section 8 (system administration), as in man-pages(7).
+const SECTIONS: &[(&str, &str)] = &[("mycli server", "8")];
+
+// Heading that marks an `after_help` text as the examples of a command (see
+// the `*_EXAMPLES` constants in src/cli.rs).
+const EXAMPLES_HEADING: &str = "Examples:";
+
+/// An environment variable read by mycli that is not bound to an argument
+/// (those are documented from their `#[arg(env)]` help text).
+struct EnvVar {
+    name: &'static str,
+    description: &'static str,
+    /// Pages besides the root page that list the variable, by command path.
+    commands: &'static [&'static str],
+}
+
+const ENVIRONMENT: &[EnvVar] = &[
+    EnvVar {
+        name: "MYCLI_LANG",
+        description: "Language of help text and messages (en, de). Takes precedence over LC_ALL, LC_MESSAGES and LANG, which are consulted in that order.",
+        commands: &[],
+    },
+    EnvVar {
+        name: "MYCLI_PAGER",
+        description: "Pager for long output such as --help-all. Falls back to PAGER, then less -FRX; cat or an empty value disables paging.",
+        commands: &[],
+    },
+    EnvVar {
+        name: "NO_COLOR",
+        description: "Disables colored output when set to a non-empty value, unless --color always is given.",
+        commands: &[],
+    },
+    EnvVar {
+        name: "MYCLI_PLUGIN_PATH",
+        description: "Directories searched for mycli-<name> plugins before PATH.",
+        commands: &["mycli plugins", "mycli plugins list", "mycli doctor"],
+    },
+    EnvVar {
+        name: "MANPATH",
+        description: "Directories searched for installed man pages before the system defaults.",
+        commands: &["mycli docs", "mycli doctor"],
+    },
+    EnvVar {
+        name: "XDG_CONFIG_HOME",
+        description: "Directory holding the mycli directory of the global configuration instead of ~/.config.",
+        commands: &["mycli config", "mycli config get", "mycli config set"],
+    },
+    EnvVar {
+        name: "VISUAL, EDITOR",
+        description: "Editor checked by doctor, VISUAL first.",
+        commands: &["mycli doctor"],
+    },
+];
+
+// Pages besides the root page whose FILES section lists the man directories,
+// and those whose FILES section lists the configuration files.
+const MAN_FILES_COMMANDS: &[&str] = &["mycli docs", "mycli doctor"];
+const CONFIG_FILES_COMMANDS: &[&str] = &["mycli config", "mycli config get", "mycli config set"];
+
+/// Package details shared by every page: the `.TH` date, source and manual,
+/// and the trailing AUTHORS, REPORTING BUGS and COPYRIGHT sections. Empty
+/// texts omit their section. Also the plugins for the root page, if any.
+#[derive(Clone, Debug, Default, Hash)]
+pub struct Header {
+    /// Date of the documented interface, `YYYY-MM-DD` (see build.rs).
+    pub date: String,
+    /// Package and version the pages come from, e.g. `mycli 0.1.0`.
+    pub source: String,
+    /// Title of the manual the pages belong to, e.g. `Mycli Manual`.
+    pub manual: String,
+    /// Authors, comma-separated.
+    pub authors: String,
+    /// Where to report bugs, as a sentence.
+    pub bugs: String,
+    /// Copyright or license notice.
+    pub copyright: String,
+    /// Plugins for a PLUGINS section of the root page (see
+    /// [`plugins::documented`]); none by default.
+    pub plugins: Vec<plugins::Documented>,
+}
+
+impl Header {
+    /// The header recorded at build time, so pages rendered by the binary
+    /// and by `cargo xtask mangen` are identical.
+    pub fn current() -> Header {
+        Header {
+            date: env!("MYCLI_MAN_DATE").to_owned(),
+            source: env!("MYCLI_MAN_SOURCE").to_owned(),
+            manual: env!("MYCLI_MAN_MANUAL").to_owned(),
+            authors: env!("MYCLI_MAN_AUTHORS").to_owned(),
+            bugs: env!("MYCLI_MAN_BUGS").to_owned(),
+            copyright: env!("MYCLI_MAN_COPYRIGHT").to_owned(),
+            plugins: Vec::new(),
+        }
+    }
+}
+
+/// Page name for a command path: `["mycli", "config", "set"]` is `mycli-config-set`.
+pub fn page_name<S: AsRef<str>>(path: &[S]) -> String {
+    path.iter().map(AsRef::as_ref).collect::<Vec<_>>().join("-")
+}
+
+/// Manual section of the page for a command path, e.g. `8` for `mycli server`.
+pub fn section<S: AsRef<str>>(path: &[S]) -> &'static str {
+    let invocation = path.iter().map(AsRef::as_ref).collect::<Vec<_>>().join(" ");
+    SECTIONS
+        .iter()
+        .find(|(command, _)| *command == invocation)
+        .map_or(SECTION, |(_, section)| section)
+}
+
+/// Subdirectory of a man directory with the pages in `lang` (`de`), where
+/// man(1) looks for the user's locale. English pages are not in one.
+pub fn lang_dir(lang: Lang) -> Option<&'static str> {
+    match lang {
+        Lang::En => None,
+        lang => Some(lang.code()),
+    }
+}
+
+/// File name of the page for a command path, e.g. `mycli-config-set.1`.
+pub fn file_name<S: AsRef<str>>(path: &[S]) -> String {
+    format!("{}.{}", page_name(path), section(path))
+}
+
+/// A page file written by [`generate_all`].
+#[derive(Clone, Debug)]
+pub struct Page {
+    pub path: PathBuf,
+    /// Manual section, which is also the `man<section>` directory it belongs in.
+    pub section: &'static str,
+    /// Whether the file was created or its contents changed.
+    pub changed: bool,
+}
+
+/// A page rendered by [`render_all`] or [`render_single`], not written
+/// anywhere yet.
+#[derive(Clone, Debug)]
+pub struct Rendered {
+    /// File name, e.g. `mycli-config-set.1` (see [`file_name`]).
+    pub file_name: String,
+    /// Manual section, which is also the `man<section>` directory it belongs in.
+    pub section: &'static str,
+    pub contents: Vec<u8>,
+}
+
+/// Render one page per (non-hidden) command of the tree, root first.
+pub fn render_all(cmd: Command, header: &Header) -> io::Result<Vec<Rendered>> {
+    let mut cmd = cmd.disable_help_subcommand(true);
+    cmd.build();
+
+    let mut rendered = Vec::new();
+    let mut path = vec![cmd.get_name().to_owned()];
+    render_tree(&cmd, None, &mut path, header, &mut rendered)?;
+    Ok(rendered)
+}
+
+fn render_tree(
+    cmd: &Command,
+    parent: Option<&Command>,
+    path: &mut Vec<String>,
+    header: &Header,
+    rendered: &mut Vec<Rendered>,
+) -> io::Result<()> {
+    rendered.push(Rendered {
+        file_name: file_name(path),
+        section: section(path),
+        contents: render_page(cmd, parent, path, header)?,
+    });
+
+    for sub in cmd.get_subcommands().filter(|s| !s.is_hide_set()) {
+        path.push(sub.get_name().to_owned());
+        render_tree(sub, Some(cmd), path, header, rendered)?;
+        path.pop();
+    }
+    Ok(())
+}
+
+/// Write one page per (non-hidden) command of the tree into `dir` and return
+/// them, root first. Pages that are already up to date are left untouched.
+pub fn generate_all(cmd: Command, header: &Header, dir: &Path) -> io::Result<Vec<Page>> {
+    render_all(cmd, header)?
+        .into_iter()
+        .map(|page| {
+            let file = dir.join(&page.file_name);
+            let changed = write_if_changed(&file, &page.contents)?;
+            Ok(Page {
+                path: file,
+                section: page.section,
+                changed,
+            })
+        })
+        .collect()
+}
+
+/// Gzip a page the way distributions ship them. The header carries no file
+/// name and the given mtime, so the output only depends on the input.
+pub fn gzip(page: &[u8], mtime: u32) -> io::Result<Vec<u8>> {
+    let mut encoder = flate2::GzBuilder::new()
+        .mtime(mtime)
+        .write(Vec::new(), flate2::Compression::best());
+    encoder.write_all(page)?;
+    encoder.finish()
+}
+
+/// Write `contents` to `file` unless it already holds exactly these bytes, so
+/// unchanged pages keep their mtime. Returns whether the file was written.
+pub fn write_if_changed(file: &Path, contents: &[u8]) -> io::Result<bool> {
+    if fs::read(file).is_ok_and(|old| old == contents) {
+        return Ok(false);
+    }
+    fs::write(file, contents)?;
+    Ok(true)
+}
+
+/// Render the complete page of `cmd`, found at `path` in the tree below `parent`.
+pub fn render_page<S: AsRef<str>>(
+    cmd: &Command,
+    parent: Option<&Command>,
+    path: &[S],
+    header: &Header,
+) -> io::Result<Vec<u8>> {
+    render(cmd, parent, path, header, false)
+}
+
+/// Render the page of the command at `path` (root first) in the tree of `cmd`.
+pub fn render_path<S: AsRef<str>>(
+    cmd: Command,
+    path: &[S],
+    header: &Header,
+) -> io::Result<Vec<u8>> {
+    let mut cmd = cmd.disable_help_subcommand(true);
+    cmd.build();
+
+    let (mut node, mut parent) = (&cmd, None);
+    for name in path.iter().skip(1).map(AsRef::as_ref) {
+        let sub = node
+            .find_subcommand(name)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("no command {name}")))?;
+        (node, parent) = (sub, Some(node));
+    }
+    render_page(node, parent, path, header)
+}
+
+/// Render the whole tree as a single root page: every subcommand becomes a
+/// subsection of COMMANDS instead of a page of its own.
+pub fn render_single(cmd: Command, header: &Header) -> io::Result<Rendered> {
+    let mut cmd = cmd.disable_help_subcommand(true);
+    cmd.build();
+
+    let path = [cmd.get_name().to_owned()];
+    Ok(Rendered {
+        file_name: file_name(&path),
+        section: section(&path),
+        contents: render(&cmd, None, &path, header, true)?,
+    })
+}
+
+// With `single`, the subcommands are rendered into COMMANDS rather than
+// referenced as pages in SUBCOMMANDS and SEE ALSO.
+fn render<S: AsRef<str>>(
+    cmd: &Command,
+    parent: Option<&Command>,
+    path: &[S],
+    header: &Header,
+    single: bool,
+) -> io::Result<Vec<u8>> {
+    let examples = cmd
+        .get_after_help()
+        .and_then(|text| parse_examples(&text.to_string()));
+
+    // Set the invocation and title from the path ourselves rather than rely on
+    // clap's propagation, so SYNOPSIS reads `mycli config get ...` and the
+    // title matches the file name for every node.
+    let invocation = path.iter().map(AsRef::as_ref).collect::<Vec<_>>().join(" ");
+    let mut page = document_args(cmd.clone()).bin_name(&invocation);
+    if examples.is_some() {
+        // Rendered as EXAMPLES below instead of clap_mangen's free-form EXTRA.
+        page = page.after_help(None::<&str>);
+    }
+    let man = Man::new(page.clone())
+        .title(page_name(path))
+        .section(section(path))
+        .date(&header.date)
+        .source(&header.source)
+        .manual(&header.manual);
+
+    let mut out = Vec::new();
+    man.render_title(&mut out)?;
+    let mut section = |render: &dyn Fn(&mut dyn Write) -> io::Result<()>| {
+        out.extend(body(render)?);
+        io::Result::Ok(())
+    };
+
+    section(&|w| man.render_name_section(w))?;
+    section(&|w| man.render_synopsis_section(w))?;
+    section(&|w| render_description(&page).to_writer(w))?;
+    if page.get_arguments().any(|a| !a.is_hide_set()) {
+        section(&|w| man.render_options_section(w))?;
+    }
+    if single {
+        section(&|w| w.write_all(&render_commands(&page, path)?))?;
+    }
+    section(&|w| render_exit_status(&exit::statuses(path)).to_writer(w))?;
+    let variables = environment(cmd, parent.is_none(), path);
+    if !variables.is_empty() {
+        section(&|w| render_environment(&variables).to_writer(w))?;
+    }
+    let man_files = parent.is_none() || MAN_FILES_COMMANDS.contains(&invocation.as_str());
+    let config_files = parent.is_none() || CONFIG_FILES_COMMANDS.contains(&invocation.as_str());
+    if man_files || config_files {
+        section(&|w| render_files(man_files, config_files).to_writer(w))?;
+    }
+    if !single && page.get_subcommands().any(|s| !s.is_hide_set()) {
+        // clap_mangen would give every child this page's section.
+        section(&|w| render_subcommands(&page, path).to_writer(w))?;
+    }
+    if parent.is_none() && !header.plugins.is_empty() {
+        section(&|w| render_plugins(&invocation, &header.plugins).to_writer(w))?;
+    }
+    if page.get_after_long_help().is_some() || page.get_after_help().is_some() {
+        section(&|w| man.render_extra_section(w))?;
+    }
+    if let Some(examples) = &examples {
+        section(&|w| render_examples(examples).to_writer(w))?;
+    }
+    if page
+        .get_version()
+        .or_else(|| page.get_long_version())
+        .is_some()
+    {
+        section(&|w| man.render_version_section(w))?;
+    }
+    for (heading, text) in [
+        ("AUTHORS", &header.authors),
+        ("REPORTING BUGS", &header.bugs),
+        ("COPYRIGHT", &header.copyright),
+    ] {
+        if !text.is_empty() {
+            section(&|w| render_paragraph(heading, text).to_writer(w))?;
+        }
+    }
+    let related = see_also(cmd, parent, path);
+    if !single && !related.is_empty() {
+        section(&|w| render_see_also(&related).to_writer(w))?;
+    }
+    Ok(out)
+}
+
+// DESCRIPTION from the long about text, which is written to read well in
+// `--help`: blank lines separate paragraphs, `- ` lines become bullet items
+// (indented lines below an item continue it) and other indented lines are
+// kept as a literal block.
+fn render_description(cmd: &Command) -> Roff {
+    let mut roff = Roff::new();
+    roff.control("SH", ["DESCRIPTION"]);
+    let Some(about) = cmd.get_long_about().or_else(|| cmd.get_about()) else {
+        return roff;
+    };
+    let about = about.to_string();
+
+    let mut in_item = false;
+    let mut literal: Vec<&str> = Vec::new();
+    let flush = |roff: &mut Roff, literal: &mut Vec<&str>| {
+        if literal.is_empty() {
+            return;
+        }
+        let indent = literal
+            .iter()
+            .map(|l| l.len() - l.trim_start().len())
+            .min()
+            .unwrap_or(0);
+        roff.control("RS", ["4"]);
+        roff.control("nf", []);
+        for line in literal.drain(..) {
+            roff.text([roman(&line[indent..])]);
+        }
+        roff.control("fi", []);
+        roff.control("RE", []);
+    };
+    for line in about.trim().lines() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() {
+            flush(&mut roff, &mut literal);
+            roff.control("PP", []);
+            in_item = false;
+        } else if let Some(item) = trimmed.strip_prefix("- ") {
+            flush(&mut roff, &mut literal);
+            roff.control("IP", [r"\(bu", "2"]);
+            roff.text([roman(item)]);
+            in_item = true;
+        } else if trimmed.len() < line.len() && !in_item {
+            literal.push(line);
+        } else {
+            roff.text([roman(trimmed)]);
+        }
+    }
+    flush(&mut roff, &mut literal);
+    roff
+}
+
+// Output of a section renderer without the roff preamble every renderer
+// writes, so sections can be concatenated into one page.
+fn body(render: &dyn Fn(&mut dyn Write) -> io::Result<()>) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    render(&mut buf)?;
+    let preamble = Roff::new().render();
+    Ok(buf
+        .strip_prefix(preamble.as_bytes())
+        .unwrap_or(&buf)
+        .to_vec())
+}
+
+// A section's body without its `.SH` line, to reuse it in a subsection.
+fn without_heading(section: Vec<u8>) -> Vec<u8> {
+    match section.iter().position(|&b| b == b'\n') {
+        Some(end) if section.starts_with(b".SH") => section[end + 1..].to_vec(),
+        _ => section,
+    }
+}
+
+// COMMANDS section of the single page: every (non-hidden) command below the
+// root as a subsection, depth first, so references between pages become
+// references to subsections.
+fn render_commands<S: AsRef<str>>(root: &Command, path: &[S]) -> io::Result<Vec<u8>> {
+    let mut heading = Roff::new();
+    heading.control("SH", ["COMMANDS"]);
+    let mut out = body(&|w| heading.to_writer(w))?;
+    let mut path: Vec<String> = path.iter().map(|p| p.as_ref().to_owned()).collect();
+    render_command_tree(root, &mut path, &mut out)?;
+    Ok(out)
+}
+
+fn render_command_tree(cmd: &Command, path: &mut Vec<String>, out: &mut Vec<u8>) -> io::Result<()> {
+    for sub in cmd.get_subcommands().filter(|s| !s.is_hide_set()) {
+        path.push(sub.get_name().to_owned());
+        out.extend(render_command(sub, path)?);
+        render_command_tree(sub, path, out)?;
+        path.pop();
+    }
+    Ok(())
+}
+
+// A man page is the long help, so help hidden only from `-h` is shown,
+// options that may be given more than once say so and possible values are
+// listed as a sentence after the help. clap_mangen already puts
+// `[default: ...]` after every option that takes a value.
+fn document_args(cmd: Command) -> Command {
+    cmd.mut_args(|arg| {
+        let arg = arg.hide_short_help(false);
+        let mut help = arg
+            .get_long_help()
+            .or_else(|| arg.get_help())
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        let repeated = matches!(arg.get_action(), ArgAction::Count | ArgAction::Append);
+        if repeated && !arg.is_positional() {
+            help = format!("{help} [may be repeated]").trim_start().to_owned();
+        }
+        let values = possible_values(&arg);
+        if !values.is_empty() {
+            help = format!("{help}\n\nPossible values: {values}.")
+                .trim_start()
+                .to_owned();
+        }
+        if help.is_empty() {
+            return arg;
+        }
+        arg.long_help(help).hide_possible_values(!values.is_empty())
+    })
+}
+
+// `plain (Plain text output), json (JSON output)`: the visible values of
+// `arg` with their help, if any.
+fn possible_values(arg: &clap::Arg) -> String {
+    arg.get_possible_values()
+        .iter()
+        .filter(|value| !value.is_hide_set())
+        .map(|value| match value.get_help() {
+            Some(help) => format!("{} ({help})", value.get_name()),
+            None => value.get_name().to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// The subsection of one command: synopsis, description, its own options (the
+// global ones are under OPTIONS), subcommands, exit statuses and examples.
+fn render_command(cmd: &Command, path: &[String]) -> io::Result<Vec<u8>> {
+    let invocation = path.join(" ");
+    let examples = cmd
+        .get_after_help()
+        .and_then(|text| parse_examples(&text.to_string()));
+    let mut page = document_args(cmd.clone())
+        .bin_name(&invocation)
+        .after_help(None::<&str>);
+    let globals: Vec<String> = page
+        .get_arguments()
+        .filter(|a| a.is_global_set())
+        .map(|a| a.get_id().to_string())
+        .collect();
+    for id in globals {
+        page = page.mut_arg(id, |a| a.hide(true));
+    }
+    let man = Man::new(page.clone());
+
+    let mut heading = Roff::new();
+    heading.control("SS", [invocation.replace('-', r"\-").as_str()]);
+    let mut out = body(&|w| heading.to_writer(w))?;
+    out.extend(without_heading(body(&|w| man.render_synopsis_section(w))?));
+    let paragraph = body(&|w| Roff::new().control("PP", []).to_writer(w))?;
+    if page.get_about().or_else(|| page.get_long_about()).is_some() {
+        out.extend_from_slice(&paragraph);
+        out.extend(without_heading(body(&|w| {
+            render_description(&page).to_writer(w)
+        })?));
+    }
+    if page.get_arguments().any(|a| !a.is_hide_set()) {
+        out.extend(without_heading(body(&|w| man.render_options_section(w))?));
+    }
+
+    let mut roff = Roff::new();
+    let children: Vec<String> = page
+        .get_subcommands()
+        .filter(|s| !s.is_hide_set())
+        .map(|s| format!("{invocation} {}", s.get_name()))
+        .collect();
+    if !children.is_empty() {
+        roff.control("PP", []);
+        roff.text([
+            roman("Subcommands, described below: "),
+            italic(children.join(", ")),
+            roman("."),
+        ]);
+    }
+    let statuses: Vec<&exit::Status> = exit::specific(path).collect();
+    if !statuses.is_empty() {
+        roff.control("PP", []);
+        roff.text([roman("Exit status:")]);
+    }
+    out.extend(body(&|w| roff.to_writer(w))?);
+    if !statuses.is_empty() {
+        out.extend(without_heading(body(&|w| {
+            render_exit_status(&statuses).to_writer(w)
+        })?));
+    }
+    if let Some(examples) = &examples {
+        out.extend(body(&|w| {
+            Roff::new()
+                .control("PP", [])
+                .text([roman("Examples:")])
+                .to_writer(w)
+        })?);
+        out.extend(without_heading(body(&|w| {
+            render_examples(examples).to_writer(w)
+        })?));
+    }
+    Ok(out)
+}
+
+// Like clap_mangen's SUBCOMMANDS section, with each child's own section number.
+fn render_subcommands<S: AsRef<str>>(cmd: &Command, path: &[S]) -> Roff {
+    let mut roff = Roff::new();
+    let heading = cmd.get_subcommand_help_heading().unwrap_or("SUBCOMMANDS");
+    roff.control("SH", [heading]);
+    for sub in cmd.get_subcommands().filter(|s| !s.is_hide_set()) {
+        let child: Vec<&str> = path
+            .iter()
+            .map(AsRef::as_ref)
+            .chain([sub.get_name()])
+            .collect();
+        roff.control("TP", []);
+        roff.text([roman(format!("{}({})", page_name(&child), section(&child)))]);
+        if let Some(about) = sub.get_about().or_else(|| sub.get_long_about()) {
+            for line in about.to_string().lines() {
+                roff.text([roman(line)]);
+            }
+        }
+    }
+    roff
+}
+
+/// One entry of an examples block: the `# ...` comment lines joined into a
+/// description, followed by the invocation lines.
+#[derive(Default)]
+pub struct Example {
+    pub description: String,
+    pub commands: Vec<String>,
+}
+
+/// Parse an `after_help` text of the form
+///
+/// ```text
+/// Examples:
+///   # Describe the example
+///   mycli do something
+///
+///   # ...
+/// ```
+///
+/// Returns `None` for any other text, which is then left to clap_mangen.
+pub fn parse_examples(text: &str) -> Option<Vec<Example>> {
+    let mut lines = text.lines();
+    if lines.next()?.trim() != EXAMPLES_HEADING {
+        return None;
+    }
+
+    let mut examples = Vec::new();
+    let mut current: Option<Example> = None;
+    for line in lines.map(str::trim) {
+        if line.is_empty() {
+            examples.extend(current.take());
+        } else if let Some(comment) = line.strip_prefix('#') {
+            let example = current.get_or_insert_with(Example::default);
+            if !example.description.is_empty() {
+                example.description.push(' ');
+            }
+            example.description.push_str(comment.trim());
+        } else {
+            current
+                .get_or_insert_with(Example::default)
+                .commands
+                .push(line.to_owned());
+        }
+    }
+    examples.extend(current);
+    examples.retain(|e| !e.commands.is_empty());
+    (!examples.is_empty()).then_some(examples)
+}
+
+fn render_exit_status(statuses: &[&exit::Status]) -> Roff {
+    let mut roff = Roff::new();
+    roff.control("SH", ["EXIT STATUS"]);
+    for status in statuses {
+        roff.control("TP", []);
+        roff.text([roman(status.label())]);
+        roff.text([roman(status.meaning)]);
+    }
+    roff
+}
+
+// Command paths of the pages related to the one at `path`: the root links its
+// commands, every other page its parent and siblings.
+fn see_also<S: AsRef<str>>(
+    cmd: &Command,
+    parent: Option<&Command>,
+    path: &[S],
+) -> Vec<Vec<String>> {
+    let words: Vec<&str> = path.iter().map(AsRef::as_ref).collect();
+    let visible = |c: &Command| -> Vec<String> {
+        c.get_subcommands()
+            .filter(|s| !s.is_hide_set() && s.get_name() != "help")
+            .map(|s| s.get_name().to_owned())
+            .collect()
+    };
+    let owned = |words: &[&str]| words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+    let child = |base: &[&str], name: &str| owned(&[base, &[name]].concat());
+
+    match (parent, words.split_last()) {
+        (Some(parent), Some((own, parent_path))) => std::iter::once(owned(parent_path))
+            .chain(
+                visible(parent)
+                    .iter()
+                    .filter(|name| name != own)
+                    .map(|name| child(parent_path, name)),
+            )
+            .collect(),
+        _ => visible(cmd)
+            .iter()
+            .map(|name| child(&words, name))
+            .collect(),
+    }
+}
+
+fn render_see_also(pages: &[Vec<String>]) -> Roff {
+    let mut roff = Roff::new();
+    roff.control("SH", ["SEE ALSO"]);
+    for (i, path) in pages.iter().enumerate() {
+        // Control arguments are not escaped by roff, text lines are.
+        let name = page_name(path).replace('-', r"\-");
+        let section = section(path);
+        let suffix = if i + 1 < pages.len() {
+            format!("({section}),")
+        } else {
+            format!("({section})")
+        };
+        roff.control("BR", [name.as_str(), suffix.as_str()]);
+    }
+    roff
+}
+
+/// Environment variables documented on the page of `cmd` at `path`, with
+/// their descriptions: those of its arguments (of the whole tree for the
+/// root page) and the ones from `ENVIRONMENT` that apply to it.
+pub fn environment<S: AsRef<str>>(cmd: &Command, root: bool, path: &[S]) -> Vec<(String, String)> {
+    let mut variables: Vec<(String, String)> = Vec::new();
+    let mut add_args = |c: &Command| {
+        // Hidden args and `hide_env` variables stay undocumented.
+        for arg in c
+            .get_arguments()
+            .filter(|a| !a.is_hide_set() && !a.is_hide_env_set())
+        {
+            let Some(name) = arg.get_env().map(|e| e.to_string_lossy().into_owned()) else {
+                continue;
+            };
+            if variables.iter().any(|(n, _)| *n == name) {
+                continue;
+            }
+            let help = arg.get_help().map(|h| h.to_string()).unwrap_or_default();
+            let description = match arg.get_long() {
+                Some(long) => format!(
+                    "{}. Same as --{long}; the option takes precedence.",
+                    help.trim_end_matches('.')
+                ),
+                None => help,
+            };
+            variables.push((name, description));
+        }
+    };
+    if root {
+        walk(cmd, &mut add_args);
+    } else {
+        add_args(cmd);
+    }
+
+    let path = path.iter().map(AsRef::as_ref).collect::<Vec<_>>().join(" ");
+    variables.extend(
+        ENVIRONMENT
+            .iter()
+            .filter(|var| root || var.commands.contains(&path.as_str()))
+            .map(|var| (var.name.to_owned(), var.description.to_owned())),
+    );
+    variables
+}
+
+fn walk(cmd: &Command, f: &mut impl FnMut(&Command)) {
+    f(cmd);
+    for sub in cmd.get_subcommands().filter(|s| !s.is_hide_set()) {
+        walk(sub, f);
+    }
+}
+
+fn render_environment(variables: &[(String, String)]) -> Roff {
+    let mut roff = Roff::new();
+    roff.control("SH", ["ENVIRONMENT"]);
+    for (name, description) in variables {
+        roff.control("TP", []);
+        roff.text([bold(name.as_str())]);
+        roff.text([roman(description.as_str())]);
+    }
+    roff
+}
+
+// PLUGINS of the root page: `mycli <name>` and what the plugin says it does.
+fn render_plugins(root: &str, plugins: &[plugins::Documented]) -> Roff {
+    let mut roff = Roff::new();
+    roff.control("SH", ["PLUGINS"]);
+    roff.text([roman(format!(
+        "External commands, run as {root} <name> from {}<name> executables on the plugin search path:",
+        plugins::PREFIX
+    ))]);
+    for plugin in plugins {
+        roff.control("TP", []);
+        roff.text([bold(format!("{root} {}", plugin.name))]);
+        if let Some(description) = &plugin.description {
+            roff.text([roman(description)]);
+        }
+    }
+    roff
+}
+
+fn render_paragraph(heading: &str, text: &str) -> Roff {
+    let mut roff = Roff::new();
+    roff.control("SH", [heading]);
+    roff.text([roman(text)]);
+    roff
+}
+
+// Documented in the Unix layout from src/paths.rs: the man directories and
+// the configuration files, as asked.
+fn render_files(man_dirs: bool, config: bool) -> Roff {
+    let mut roff = Roff::new();
+    roff.control("SH", ["FILES"]);
+    if man_dirs {
+        render_man_dirs(&mut roff);
+    }
+    if config {
+        roff.control("TP", []);
+        roff.text([italic(format!(
+            "{}/{}",
+            paths::CONFIG_ROOT,
+            paths::CONFIG_FILE
+        ))]);
+        roff.text([roman(format!(
+            "Global configuration, written by config set --global. $XDG_CONFIG_HOME/mycli/{} when XDG_CONFIG_HOME is set.",
+            paths::CONFIG_FILE
+        ))]);
+        roff.control("TP", []);
+        roff.text([italic(paths::LOCAL_CONFIG)]);
+        roff.text([roman(
+            "Local configuration of the working directory, written by config set. config get looks here before the global configuration.",
+        )]);
+    }
+    roff
+}
+
+fn render_man_dirs(roff: &mut Roff) {
+    // Stack the directories as tags of a single paragraph, restoring the
+    // spacing once its body is done.
+    roff.control("PD", ["0"]);
+    for prefix in paths::MAN_PREFIXES {
+        roff.control("TP", []);
+        roff.text([italic(format!("{prefix}/man{SECTION}/"))]);
+    }
+    let others: Vec<String> = SECTIONS
+        .iter()
+        .map(|(command, section)| format!("{command} in man{section}/"))
+        .collect();
+    roff.text([roman(format!(
+        "Installed man pages, searched in this order after MANPATH by docs and doctor, with {}. docs --install writes to the first one, or with --user to the last.",
+        others.join(", ")
+    ))]);
+    roff.control("PD", []);
+}
+
+fn render_examples(examples: &[Example]) -> Roff {
+    let mut roff = Roff::new();
+    roff.control("SH", ["EXAMPLES"]);
+    for example in examples {
+        roff.control("TP", []);
+        roff.text([roman(example.description.as_str())]);
+        // Literal, unfilled block so invocations keep their spacing.
+        roff.control("RS", ["4"]);
+        roff.control("nf", []);
+        for command in &example.commands {
+            roff.text([roman(command.as_str())]);
+        }
+        roff.control("fi", []);
+        roff.control("RE", []);
+    }
+    roff
+}
@@ -0,0 +1,326 @@
+// Plain-text rendering of our man pages, for `mycli docs` on systems
+// without man(1).
+//
+// This is not a roff implementation: it understands the requests src/man.rs
+// and clap_mangen emit (.TH, .SH, .SS, .PP, .TP, .IP, .RS/.RE, .nf/.fi, .br,
+// .PD, .B/.I/.BR) and the escapes they use, and lays the text out roughly
+// like man does: headings at the margin, body text indented by 7 columns.
+
+// Indentation of body text and of `.TP` item bodies, as in man(1).
+const INDENT: usize = 7;
+
+/// Render roff `source` as plain text wrapped to `width` columns.
+pub fn render(source: &str, width: usize) -> String {
+    let mut page = Page {
+        width,
+        prevailing: INDENT,
+        ..Page::default()
+    };
+    for line in source.lines() {
+        match line.strip_prefix('.') {
+            Some(request) => page.request(request),
+            // `'` starts a request that does not cause a break (the
+            // preamble); none of them print anything.
+            None if line.starts_with('\'') => {}
+            None => page.text(line),
+        }
+    }
+    page.flush();
+    page.out.trim_end().to_owned() + "\n"
+}
+
+#[derive(Default)]
+struct Page {
+    out: String,
+    width: usize,
+    /// Left margin of the current section and `.RS` blocks.
+    indent: usize,
+    /// `.RS` margins to restore with `.RE`.
+    saved: Vec<usize>,
+    /// Extra indentation of paragraph text inside a `.TP`/`.IP` item.
+    offset: usize,
+    /// Width of the last `.TP`/`.IP` item, the default shift of `.RS`.
+    prevailing: usize,
+    /// Item tag printed before the next paragraph.
+    lead: Option<String>,
+    /// The next text line is a `.TP` tag.
+    expect_tag: bool,
+    /// `.nf` is active: lines are printed as they are.
+    literal: bool,
+    /// `.PD 0`: no blank line between items.
+    tight: bool,
+    /// Words of the paragraph being filled.
+    words: Vec<String>,
+    /// Length of `out` right after the last heading, which is not followed
+    /// by a blank line.
+    heading_end: usize,
+}
+
+impl Page {
+    fn request(&mut self, request: &str) {
+        let (name, args) = request.split_once(' ').unwrap_or((request, ""));
+        let args = split_args(args);
+        match name {
+            "TH" => {
+                let title = match (args.first(), args.get(1)) {
+                    (Some(name), Some(section)) => format!("{name}({section})"),
+                    _ => String::new(),
+                };
+                // `mycli(1)    Mycli Manual    mycli(1)`, or just the title
+                // when the manual name does not fit between.
+                let manual = args.get(4).map_or(String::new(), |m| unescape(m));
+                let inner = self.width.saturating_sub(2 * title.chars().count());
+                let line = match manual.chars().count() {
+                    0 => title,
+                    len if len + 2 > inner => title,
+                    len => {
+                        let left = (inner - len) / 2;
+                        let right = inner - len - left;
+                        format!(
+                            "{title}{}{manual}{}{title}",
+                            " ".repeat(left),
+                            " ".repeat(right)
+                        )
+                    }
+                };
+                self.out.push_str(&line);
+                self.out.push_str("\n\n");
+            }
+            "SH" | "SS" => {
+                self.flush();
+                self.blank();
+                let margin = if name == "SH" { 0 } else { 3 };
+                self.line(margin, &args.join(" "));
+                self.heading_end = self.out.len();
+                self.indent = INDENT;
+                self.saved.clear();
+                self.offset = 0;
+                self.prevailing = INDENT;
+            }
+            "PP" | "P" | "LP" => {
+                self.flush();
+                self.blank();
+                self.offset = 0;
+                self.prevailing = INDENT;
+            }
+            "TP" => {
+                self.flush();
+                self.item_gap();
+                self.offset = INDENT;
+                self.prevailing = INDENT;
+                self.expect_tag = true;
+            }
+            "IP" => {
+                self.flush();
+                self.item_gap();
+                self.offset = args.get(1).and_then(|n| n.parse().ok()).unwrap_or(INDENT);
+                self.prevailing = self.offset;
+                self.lead = args.first().map(|tag| unescape(tag));
+            }
+            "RS" => {
+                self.flush();
+                self.saved.push(self.indent);
+                // Without an argument, by the width of the last item, which
+                // lines the block up with that item's body.
+                let by = args.first().and_then(|n| n.parse().ok());
+                self.indent += by.unwrap_or(self.prevailing);
+                self.offset = 0;
+            }
+            "RE" => {
+                self.flush();
+                self.indent = self.saved.pop().unwrap_or(INDENT);
+            }
+            "nf" => {
+                self.flush();
+                self.literal = true;
+            }
+            "fi" => self.literal = false,
+            "br" => self.flush(),
+            "PD" => self.tight = args.first().is_some_and(|n| n == "0"),
+            "B" | "I" => self.text(&args.join(" ")),
+            // Alternating fonts: the arguments are joined without spaces.
+            "BR" | "BI" | "IR" | "RB" | "RI" | "IB" => self.text(&args.concat()),
+            // Comments and requests that only set up fonts or strings.
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, line: &str) {
+        let text = unescape(line);
+        if self.expect_tag {
+            self.expect_tag = false;
+            self.lead = Some(text);
+        } else if self.literal {
+            let margin = self.indent + self.offset;
+            self.line(margin, text.trim_end());
+        } else if text.trim().is_empty() {
+            // A blank line ends the paragraph, as in roff.
+            self.flush();
+            self.blank();
+        } else {
+            self.words
+                .extend(text.split_whitespace().map(str::to_owned));
+        }
+    }
+
+    // Fill and print the pending paragraph, starting with the item tag if
+    // one is waiting.
+    fn flush(&mut self) {
+        let margin = self.indent + self.offset;
+        let mut current = match self.lead.take() {
+            // A tag that leaves room starts the first line, as in man(1).
+            Some(lead) if lead.chars().count() < self.offset && !self.words.is_empty() => {
+                let pad = self.offset - lead.chars().count();
+                format!("{}{lead}{}", " ".repeat(self.indent), " ".repeat(pad))
+            }
+            Some(lead) => {
+                self.line(self.indent, &lead);
+                String::new()
+            }
+            None => String::new(),
+        };
+        for word in self.words.drain(..) {
+            let len = current.chars().count();
+            if len == 0 {
+                current = format!("{}{word}", " ".repeat(margin));
+            } else if len > margin && len + 1 + word.chars().count() > self.width {
+                self.out.push_str(&current);
+                self.out.push('\n');
+                current = format!("{}{word}", " ".repeat(margin));
+            } else if current.ends_with(' ') {
+                current.push_str(&word);
+            } else {
+                current.push(' ');
+                current.push_str(&word);
+            }
+        }
+        if !current.trim().is_empty() {
+            self.out.push_str(current.trim_end());
+            self.out.push('\n');
+        }
+    }
+
+    fn line(&mut self, margin: usize, text: &str) {
+        self.out.push_str(&" ".repeat(margin));
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    fn blank(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with("\n\n") && self.out.len() != self.heading_end
+        {
+            self.out.push('\n');
+        }
+    }
+
+    fn item_gap(&mut self) {
+        if !self.tight {
+            self.blank();
+        }
+    }
+}
+
+// Arguments of a request: space separated, with `"..."` grouping.
+fn split_args(args: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut rest = args.trim_start();
+    while !rest.is_empty() {
+        let (arg, tail) = match rest.strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+            None => rest.split_once(' ').unwrap_or((rest, "")),
+        };
+        out.push(arg.to_owned());
+        rest = tail.trim_start();
+    }
+    out
+}
+
+// Replace the escapes in `text` with the characters they print and drop
+// font changes.
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            // \fB, \fR, \f(CW: font changes.
+            Some('f') => {
+                if chars.next() == Some('(') {
+                    chars.nth(1);
+                }
+            }
+            // \(xx and \*(xx: special characters and strings.
+            Some('(') => out.push_str(special(&take2(&mut chars))),
+            Some('*') => match chars.next() {
+                Some('(') => out.push_str(special(&take2(&mut chars))),
+                Some(name) => out.push_str(special(&name.to_string())),
+                None => {}
+            },
+            Some('e') => out.push('\\'),
+            Some('&') | None => {}
+            Some(other) => out.push(other),
+        }
+    }
+    out
+}
+
+fn take2(chars: &mut std::str::Chars<'_>) -> String {
+    chars.take(2).collect()
+}
+
+fn special(name: &str) -> &'static str {
+    match name {
+        "bu" => "•",
+        "aq" | "Aq" => "'",
+        "dq" => "\"",
+        "em" => "—",
+        "en" => "–",
+        "hy" | "mi" => "-",
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headings_items_and_escapes() {
+        let page = ".TH mycli 1 2024-01-01 \"mycli 0.1.0\" \"Mycli Manual\"\n\
+                    .SH NAME\nmycli \\- Example\n\
+                    .SH OPTIONS\n.TP\n\\fB\\-q\\fR, \\fB\\-\\-quiet\\fR\nBe quiet\n\
+                    .TP\n\\fB\\-x\\fR\nShort tag\n";
+        assert_eq!(
+            render(page, 60),
+            "mycli(1)                Mycli Manual                mycli(1)\n\
+             \n\
+             NAME\n       mycli - Example\n\
+             \n\
+             OPTIONS\n       -q, --quiet\n              Be quiet\n\
+             \n       -x     Short tag\n"
+        );
+    }
+
+    #[test]
+    fn fills_paragraphs_to_the_width() {
+        let page = ".SH DESCRIPTION\none two three four five six\n.PP\nseven";
+        assert_eq!(
+            render(page, 20),
+            "DESCRIPTION\n       one two three\n       four five six\n\n       seven\n"
+        );
+    }
+
+    #[test]
+    fn literal_blocks_and_rs_shift_by_the_item_width() {
+        let page = ".SH EXAMPLES\n.TP\nList\n.RS 4\n.nf\nmycli  a\nmycli b\n.fi\n.RE\n\
+                    .IP \\(bu 2\nbullet\n.RS\nunder it\n.RE";
+        assert_eq!(
+            render(page, 60),
+            "EXAMPLES\n       List\n           mycli  a\n           mycli b\n\
+             \n       • bullet\n         under it\n"
+        );
+    }
+}
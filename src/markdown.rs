@@ -0,0 +1,299 @@
+// Markdown reference for every command, written by `mycli docs --markdown`,
+// and the same pages as an mdBook source tree for `mycli docs --book`.
+//
+// One `<page>.md` per command, named like the man pages (`mycli-config-get.md`)
+// so links between parent and child pages are plain relative file names. The
+// output only depends on the CLI definition, which keeps it diffable when
+// committed (e.g. under docs/cli/).
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use clap::{Arg, Command};
+
+use crate::exit;
+use crate::introspect;
+use crate::man;
+use crate::plugins::{self, Documented};
+
+/// Write one Markdown file per (non-hidden) command of the tree into `dir`
+/// and return the written paths, root first. `plugins` are listed on the
+/// root page.
+pub fn generate_all(cmd: Command, plugins: &[Documented], dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut cmd = cmd.disable_help_subcommand(true);
+    cmd.build();
+
+    let mut written = Vec::new();
+    let mut path = vec![cmd.get_name().to_owned()];
+    generate_tree(&cmd, plugins, &mut path, dir, &mut written)?;
+    Ok(written)
+}
+
+/// Write an mdBook source tree into `dir`: `book.toml`, and under `src/` a
+/// chapter per (non-hidden) command nested like the command tree in
+/// `SUMMARY.md`, plus the `--dump-cli-json` output as `mycli-cli.schema.json`.
+/// Returns the written paths; `mdbook build DIR` turns them into HTML.
+pub fn generate_book(cmd: Command, plugins: &[Documented], dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut cmd = cmd.disable_help_subcommand(true);
+    cmd.build();
+    let name = cmd.get_name().to_owned();
+    let src = dir.join("src");
+    fs::create_dir_all(&src)?;
+
+    let book = dir.join("book.toml");
+    fs::write(
+        &book,
+        format!("[book]\ntitle = \"{name} command reference\"\nsrc = \"src\"\n"),
+    )?;
+    let mut written = vec![book];
+
+    let mut chapters = Vec::new();
+    generate_tree(&cmd, plugins, &mut vec![name.clone()], &src, &mut chapters)?;
+    let root = &chapters[0];
+    let mut page = fs::read_to_string(root)?;
+    let schema = introspect::SCHEMA_FILE;
+    let _ = writeln!(
+        page,
+        "\nThe whole command tree is also available as JSON, in the format of \
+         `{name} --dump-cli-json`: [{schema}]({schema})."
+    );
+    fs::write(root, page)?;
+
+    // The root chapter is the introduction, its commands the numbered parts.
+    let mut summary = format!("# Summary\n\n[{name}]({}.md)\n\n", man::page_name(&[&name]));
+    summary_tree(&cmd, &mut vec![name], 0, &mut summary);
+    let summary_file = src.join("SUMMARY.md");
+    fs::write(&summary_file, summary)?;
+    written.push(summary_file);
+    written.extend(chapters);
+
+    let json = src.join(introspect::SCHEMA_FILE);
+    fs::write(&json, introspect::to_json(cmd))?;
+    written.push(json);
+    Ok(written)
+}
+
+// `SUMMARY.md` entries for the subcommands of `cmd`, two spaces of
+// indentation per level.
+fn summary_tree(cmd: &Command, path: &mut Vec<String>, depth: usize, out: &mut String) {
+    for sub in cmd.get_subcommands().filter(|s| !s.is_hide_set()) {
+        path.push(sub.get_name().to_owned());
+        let _ = writeln!(
+            out,
+            "{}- [{}]({}.md)",
+            "  ".repeat(depth),
+            path.join(" "),
+            man::page_name(path)
+        );
+        summary_tree(sub, path, depth + 1, out);
+        path.pop();
+    }
+}
+
+fn generate_tree(
+    cmd: &Command,
+    plugins: &[Documented],
+    path: &mut Vec<String>,
+    dir: &Path,
+    written: &mut Vec<PathBuf>,
+) -> io::Result<()> {
+    let file = dir.join(format!("{}.md", man::page_name(path)));
+    fs::write(&file, render(cmd, path, plugins))?;
+    written.push(file);
+
+    for sub in cmd.get_subcommands().filter(|s| !s.is_hide_set()) {
+        path.push(sub.get_name().to_owned());
+        generate_tree(sub, &[], path, dir, written)?;
+        path.pop();
+    }
+    Ok(())
+}
+
+/// Render the page of `cmd`, found at `path` in the tree, listing `plugins`
+/// after its commands.
+pub fn render(cmd: &Command, path: &[String], plugins: &[Documented]) -> String {
+    let mut out = String::new();
+    let invocation = path.join(" ");
+    let _ = writeln!(out, "# {invocation}\n");
+    if let Some((_, parent)) = path.split_last().filter(|(_, p)| !p.is_empty()) {
+        let _ = writeln!(
+            out,
+            "Part of [`{}`]({}.md).\n",
+            parent.join(" "),
+            man::page_name(parent)
+        );
+    }
+    if let Some(about) = cmd.get_long_about().or_else(|| cmd.get_about()) {
+        // Indented help lines would turn into code blocks; `- ` items stay lists.
+        let about = about.to_string();
+        let lines: Vec<&str> = about.trim().lines().map(str::trim_start).collect();
+        let _ = writeln!(out, "{}\n", lines.join("\n"));
+    }
+
+    let usage = cmd.clone().bin_name(&invocation).render_usage().to_string();
+    let usage = usage.trim().trim_start_matches("Usage:").trim();
+    let _ = writeln!(out, "## Usage\n\n```text\n{usage}\n```\n");
+
+    let visible: Vec<&Arg> = cmd.get_arguments().filter(|a| !a.is_hide_set()).collect();
+    let positionals: Vec<&Arg> = visible
+        .iter()
+        .copied()
+        .filter(|a| a.is_positional())
+        .collect();
+    let options: Vec<&Arg> = visible
+        .iter()
+        .copied()
+        .filter(|a| !a.is_positional())
+        .collect();
+    if !positionals.is_empty() {
+        out.push_str("## Arguments\n\n");
+        args_table(&mut out, &positionals);
+    }
+    if !options.is_empty() {
+        out.push_str("## Options\n\n");
+        args_table(&mut out, &options);
+    }
+
+    let subcommands: Vec<&Command> = cmd.get_subcommands().filter(|s| !s.is_hide_set()).collect();
+    if !subcommands.is_empty() {
+        out.push_str("## Commands\n\n");
+        for sub in subcommands {
+            let child = [path, &[sub.get_name().to_owned()]].concat();
+            let about = sub.get_about().map(|a| a.to_string()).unwrap_or_default();
+            let _ = writeln!(
+                out,
+                "- [`{}`]({}.md): {about}",
+                child.join(" "),
+                man::page_name(&child)
+            );
+        }
+        out.push('\n');
+    }
+
+    if !plugins.is_empty() {
+        let _ = writeln!(
+            out,
+            "## Plugins\n\nExternal commands, run as `{invocation} <name>` from `{}<name>` \
+             executables on the plugin search path:\n",
+            plugins::PREFIX
+        );
+        for plugin in plugins {
+            match &plugin.description {
+                Some(description) => {
+                    let _ = writeln!(out, "- `{invocation} {}`: {description}", plugin.name);
+                }
+                None => {
+                    let _ = writeln!(out, "- `{invocation} {}`", plugin.name);
+                }
+            }
+        }
+        out.push('\n');
+    }
+
+    out.push_str("## Exit status\n\n| Status | Meaning |\n| --- | --- |\n");
+    for status in exit::statuses(path) {
+        let _ = writeln!(out, "| {} | {} |", status.label(), cell(status.meaning));
+    }
+    out.push('\n');
+
+    let variables = man::environment(cmd, path.len() == 1, path);
+    if !variables.is_empty() {
+        out.push_str("## Environment\n\n");
+        for (name, description) in variables {
+            // `<name>` placeholders would read as HTML tags.
+            let description = description.replace('<', "\\<");
+            let _ = writeln!(out, "- `{name}`: {description}");
+        }
+        out.push('\n');
+    }
+
+    if let Some(examples) = cmd
+        .get_after_help()
+        .and_then(|text| man::parse_examples(&text.to_string()))
+    {
+        out.push_str("## Examples\n\n");
+        for example in examples {
+            let _ = writeln!(out, "{}:\n", example.description);
+            let _ = writeln!(out, "```sh\n{}\n```\n", example.commands.join("\n"));
+        }
+    }
+
+    // Exactly one trailing newline.
+    let trimmed = out.trim_end().len();
+    out.truncate(trimmed);
+    out.push('\n');
+    out
+}
+
+fn args_table(out: &mut String, args: &[&Arg]) {
+    out.push_str("| Argument | Description | Default | Environment | Values |\n");
+    out.push_str("| --- | --- | --- | --- | --- |\n");
+    for arg in args {
+        // Flags default to off; only values are worth listing.
+        let defaults: Vec<String> = arg
+            .get_default_values()
+            .iter()
+            .filter(|_| arg.get_action().takes_values())
+            .map(|v| format!("`{}`", v.to_string_lossy()))
+            .collect();
+        let env = arg
+            .get_env()
+            .filter(|_| !arg.is_hide_env_set())
+            .map(|e| format!("`{}`", e.to_string_lossy()))
+            .unwrap_or_default();
+        let values: Vec<String> = arg
+            .get_possible_values()
+            .iter()
+            .filter(|v| !v.is_hide_set())
+            .map(|v| format!("`{}`", v.get_name()))
+            .collect();
+        let help = arg.get_help().map(|h| h.to_string()).unwrap_or_default();
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {env} | {} |",
+            cell(&signature(arg)),
+            cell(&help),
+            defaults.join(", "),
+            values.join(", "),
+        );
+    }
+    out.push('\n');
+}
+
+// `-p, --port <PORT>` for options, `<KEY>` for positionals.
+fn signature(arg: &Arg) -> String {
+    let value = arg
+        .get_value_names()
+        .map(|names| {
+            names
+                .iter()
+                .map(|n| format!("<{n}>"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .unwrap_or_else(|| format!("<{}>", arg.get_id().as_str().to_uppercase()));
+    if arg.is_positional() {
+        return format!("`{value}`");
+    }
+
+    let mut names = Vec::new();
+    if let Some(short) = arg.get_short() {
+        names.push(format!("-{short}"));
+    }
+    if let Some(long) = arg.get_long() {
+        names.push(format!("--{long}"));
+    }
+    let mut signature = names.join(", ");
+    if arg.get_action().takes_values() {
+        signature.push(' ');
+        signature.push_str(&value);
+    }
+    format!("`{signature}`")
+}
+
+// Keep table cells on one line and their pipes literal.
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', "<br>")
+}
@@ -0,0 +1,241 @@
+// Output helpers shared by commands that print to stdout.
+
+use std::env;
+use std::io::{self, Write};
+use std::process::{Child, Command, Stdio};
+
+use serde_json::Value;
+
+use crate::cli::{Cli, ColorWhen};
+use crate::i18n::Lang;
+use crate::Context;
+
+// SGR codes of the JSON token kinds, as jq colors them where it can.
+const KEY: &str = "1;34";
+const STRING: &str = "32";
+const NUMBER: &str = "33";
+const LITERAL: &str = "35";
+
+// Quit if the text fits on one screen, keep colors, don't clear the screen.
+const DEFAULT_PAGER: &str = "less -FRX";
+
+/// How one run writes its results: the global output flags, with `--color`
+/// resolved for its stdout. The default is plain English without a pager.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Style {
+    /// `--quiet`: informational lines are dropped.
+    pub quiet: bool,
+    /// `--pretty`: JSON is indented.
+    pub pretty: bool,
+    /// Whether JSON is highlighted.
+    pub color: bool,
+    /// Whether stdout is a terminal, so long text may be paged.
+    pub interactive: bool,
+    /// `--no-pager`.
+    pub no_pager: bool,
+    /// Language of messages.
+    pub lang: Lang,
+}
+
+impl Style {
+    /// The style `opts` asks for on the stdout of `ctx`.
+    pub fn new(opts: &Cli, ctx: &Context) -> Style {
+        Style {
+            quiet: opts.quiet,
+            pretty: opts.pretty,
+            color: use_color(opts.color, ctx.interactive),
+            interactive: ctx.interactive,
+            no_pager: opts.no_pager,
+            lang: ctx.lang,
+        }
+    }
+
+    /// Serialize a `--format json` result: one line by default, so every
+    /// record is exactly one line in a pipeline, or indented by two spaces
+    /// with `--pretty`. Keys are sorted either way, which keeps exports
+    /// diffable.
+    pub fn json(&self, value: &Value) -> String {
+        json_with(value, self.pretty, self.color)
+    }
+
+    /// Serialize a `--format ndjson` record: always one line, `--pretty` or
+    /// not, since the line is the record separator.
+    pub fn json_line(&self, value: &Value) -> String {
+        json_with(value, false, self.color)
+    }
+
+    /// Write `text` to `out`, paged as [`page`] decides for this style.
+    pub fn page(&self, out: &mut dyn Write, text: &str) -> io::Result<()> {
+        page(out, self.interactive, text, self.no_pager)
+    }
+}
+
+/// Write `text` to `out`, paging it when `interactive` and it does not fit on
+/// the terminal.
+///
+/// The pager is `MYCLI_PAGER`, then `PAGER`, then `less -FRX`. Output is written
+/// directly when paging is disabled (`--no-pager`, or a pager of `cat`/empty),
+/// when nobody watches the output, when the text fits, or when the pager cannot
+/// be spawned.
+pub fn page(out: &mut dyn Write, interactive: bool, text: &str, no_pager: bool) -> io::Result<()> {
+    if !no_pager && interactive && !fits_on_screen(text) {
+        if let Some(child) = pager_command().and_then(|pager| spawn_pager(&pager).ok()) {
+            return feed_pager(child, text);
+        }
+    }
+
+    out.write_all(text.as_bytes())
+}
+
+/// Whether `--color <when>` colors output that is `interactive` or not:
+/// `auto` colors a terminal unless NO_COLOR is set to a non-empty value.
+pub fn use_color(when: ColorWhen, interactive: bool) -> bool {
+    match when {
+        ColorWhen::Always => true,
+        ColorWhen::Never => false,
+        ColorWhen::Auto => interactive && env::var_os("NO_COLOR").is_none_or(|v| v.is_empty()),
+    }
+}
+
+// `Style::json` and `Style::json_line`, without a whole style.
+fn json_with(value: &Value, pretty: bool, color: bool) -> String {
+    // serde_json's default map is ordered by key, and its compact form
+    // escapes every newline inside strings.
+    let text = if pretty {
+        serde_json::to_string_pretty(value)
+    } else {
+        serde_json::to_string(value)
+    }
+    .expect("JSON values serialize");
+    // Working on the text rather than the value keeps the uncolored output
+    // byte for byte the same.
+    if color {
+        highlight(&text)
+    } else {
+        text
+    }
+}
+
+// Wrap every key, string, number and literal of `json`, which must be valid
+// serde_json output, in its color. Punctuation and whitespace stay plain.
+fn highlight(json: &str) -> String {
+    let mut out = String::with_capacity(json.len() * 2);
+    let mut rest = json;
+    while let Some(c) = rest.chars().next() {
+        let len = match c {
+            '"' => string_len(rest),
+            '-' | '0'..='9' => rest
+                .find(|c: char| !matches!(c, '-' | '+' | '.' | 'e' | 'E' | '0'..='9'))
+                .unwrap_or(rest.len()),
+            't' | 'f' | 'n' => rest
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(rest.len()),
+            _ => c.len_utf8(),
+        };
+        let (token, tail) = rest.split_at(len);
+        let color = match c {
+            // A string followed by `:` is an object key.
+            '"' if tail.trim_start().starts_with(':') => Some(KEY),
+            '"' => Some(STRING),
+            '-' | '0'..='9' => Some(NUMBER),
+            't' | 'f' | 'n' => Some(LITERAL),
+            _ => None,
+        };
+        match color {
+            Some(color) => {
+                out.push_str(&format!("\x1b[{color}m{token}\x1b[0m"));
+            }
+            None => out.push_str(token),
+        }
+        rest = tail;
+    }
+    out
+}
+
+// Length of the string literal at the start of `json`, quotes included.
+fn string_len(json: &str) -> usize {
+    let mut escaped = false;
+    for (i, c) in json.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return i + 1,
+            _ => {}
+        }
+    }
+    json.len()
+}
+
+fn pager_command() -> Option<String> {
+    let pager = env::var("MYCLI_PAGER")
+        .or_else(|_| env::var("PAGER"))
+        .unwrap_or_else(|_| DEFAULT_PAGER.to_owned());
+    match pager.trim() {
+        "" | "cat" => None,
+        pager => Some(pager.to_owned()),
+    }
+}
+
+fn fits_on_screen(text: &str) -> bool {
+    match terminal_size::terminal_size() {
+        Some((_, terminal_size::Height(h))) => text.lines().count() < usize::from(h),
+        None => true,
+    }
+}
+
+fn spawn_pager(pager: &str) -> io::Result<Child> {
+    let mut parts = pager.split_whitespace();
+    let program = parts.next().unwrap_or(DEFAULT_PAGER);
+    Command::new(program)
+        .args(parts)
+        .stdin(Stdio::piped())
+        .spawn()
+}
+
+fn feed_pager(mut child: Child, text: &str) -> io::Result<()> {
+    if let Some(mut stdin) = child.stdin.take() {
+        // The user may quit the pager before reading everything.
+        match stdin.write_all(text.as_bytes()) {
+            Err(e) if e.kind() != io::ErrorKind::BrokenPipe => return Err(e),
+            _ => {}
+        }
+    }
+    child.wait()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlight_colors_each_token_kind() {
+        assert_eq!(
+            highlight(r#"{"k":["s",-1.5e3,true,null]}"#),
+            "{\x1b[1;34m\"k\"\x1b[0m:[\x1b[32m\"s\"\x1b[0m,\x1b[33m-1.5e3\x1b[0m,\
+             \x1b[35mtrue\x1b[0m,\x1b[35mnull\x1b[0m]}"
+        );
+    }
+
+    #[test]
+    fn highlight_keeps_escaped_quotes_inside_strings() {
+        assert_eq!(
+            highlight(r#"{"a \"b\": c" : "d\\"}"#),
+            "{\x1b[1;34m\"a \\\"b\\\": c\"\x1b[0m : \x1b[32m\"d\\\\\"\x1b[0m}"
+        );
+    }
+
+    #[test]
+    fn pretty_indents_and_compact_stays_on_one_line() {
+        let value = serde_json::json!({ "a": [1, 2], "b": "x\ny" });
+        assert_eq!(json_with(&value, false, false), r#"{"a":[1,2],"b":"x\ny"}"#);
+        assert_eq!(
+            json_with(&value, true, false),
+            "{\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": \"x\\ny\"\n}"
+        );
+        assert_eq!(
+            json_with(&serde_json::json!([1]), false, true),
+            "[\x1b[33m1\x1b[0m]"
+        );
+    }
+}
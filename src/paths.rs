@@ -0,0 +1,57 @@
+// Well-known file locations, also documented in the FILES section of the
+// man pages (src/man.rs).
+//
+// Locations are written in their documented Unix form, with `~/` standing for
+// the user's home directory, and expanded when used.
+
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Where `docs --install` puts pages by default.
+pub const INSTALL_MAN_PREFIX: &str = "/usr/local/share/man";
+
+/// Where `docs --install --user` puts pages.
+pub const USER_MAN_PREFIX: &str = "~/.local/share/man";
+
+/// Man page prefixes searched after `MANPATH`, in order.
+pub const MAN_PREFIXES: &[&str] = &[INSTALL_MAN_PREFIX, "/usr/share/man", USER_MAN_PREFIX];
+
+/// Directory of the global configuration when `XDG_CONFIG_HOME` is unset.
+pub const CONFIG_ROOT: &str = "~/.config/mycli";
+
+/// The global configuration file in the config root.
+pub const CONFIG_FILE: &str = "config.json";
+
+/// The local configuration file, relative to the working directory.
+pub const LOCAL_CONFIG: &str = ".mycli/config.json";
+
+/// Expand a leading `~/` to the home directory. `None` if there is no `HOME`.
+pub fn expand(path: &str) -> Option<PathBuf> {
+    match path.strip_prefix("~/") {
+        Some(rest) => Some(Path::new(&env::var_os("HOME")?).join(rest)),
+        None => Some(PathBuf::from(path)),
+    }
+}
+
+/// Install locations searched for pages: `MANPATH` entries followed by
+/// `MAN_PREFIXES`.
+pub fn man_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = env::var_os("MANPATH")
+        .map(|value| {
+            env::split_paths(&value)
+                .filter(|p| !p.as_os_str().is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    dirs.extend(MAN_PREFIXES.iter().filter_map(|prefix| expand(prefix)));
+    dirs
+}
+
+/// Directory of the global configuration: `$XDG_CONFIG_HOME/mycli`, or
+/// `CONFIG_ROOT`. `None` if neither `XDG_CONFIG_HOME` nor `HOME` is set.
+pub fn config_root() -> Option<PathBuf> {
+    match env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+        Some(dir) => Some(Path::new(&dir).join("mycli")),
+        None => expand(CONFIG_ROOT),
+    }
+}
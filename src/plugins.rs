@@ -0,0 +1,137 @@
+// Discovery of git-style external subcommands: an executable named
+// `mycli-<name>` on the search path is run for `mycli <name> ...`.
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// File name prefix of plugin executables.
+pub const PREFIX: &str = "mycli-";
+
+/// Comma-separated plugin names to list in generated documentation.
+pub const DOC_PLUGINS_VAR: &str = "MYCLI_DOC_PLUGINS";
+
+/// An external subcommand found on the search path.
+#[derive(Debug, Clone)]
+pub struct Plugin {
+    /// Subcommand name, i.e. the file name without `mycli-` and extension
+    pub name: String,
+    /// Full path to the executable
+    pub path: PathBuf,
+}
+
+/// Directories searched for plugins: `MYCLI_PLUGIN_PATH` first, then `PATH`.
+pub fn search_path() -> Vec<PathBuf> {
+    ["MYCLI_PLUGIN_PATH", "PATH"]
+        .iter()
+        .filter_map(env::var_os)
+        .flat_map(|value| env::split_paths(&value).collect::<Vec<_>>())
+        .collect()
+}
+
+/// All plugins in the directories of `search_path`, sorted by name. When a
+/// name appears in several directories the first one wins, matching how it
+/// would be run.
+pub fn discover(search_path: &[PathBuf]) -> Vec<Plugin> {
+    let mut plugins: Vec<Plugin> = Vec::new();
+    for dir in search_path {
+        let Ok(entries) = dir.read_dir() else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Some(name) = plugin_name(&path) else {
+                continue;
+            };
+            if is_executable(&path) && !plugins.iter().any(|p| p.name == name) {
+                plugins.push(Plugin { name, path });
+            }
+        }
+    }
+    plugins.sort_by(|a, b| a.name.cmp(&b.name));
+    plugins
+}
+
+/// The plugin in `search_path` that `mycli <name>` would run, if any.
+pub fn find(search_path: &[PathBuf], name: &str) -> Option<Plugin> {
+    let file = format!("{PREFIX}{name}{}", env::consts::EXE_SUFFIX);
+    search_path
+        .iter()
+        .map(|dir| dir.join(&file))
+        .find(|path| is_executable(path))
+        .map(|path| Plugin {
+            name: name.to_owned(),
+            path,
+        })
+}
+
+/// A plugin as listed in the PLUGINS section of generated documentation.
+#[derive(Debug, Clone, Hash)]
+pub struct Documented {
+    pub name: String,
+    /// Its `--plugin-describe` line, if it has one.
+    pub description: Option<String>,
+}
+
+/// Plugins to list in generated documentation: the names in
+/// `MYCLI_DOC_PLUGINS`, then with `scan` every plugin in `search_path`.
+/// Names that are not installed or cannot describe themselves are listed
+/// without a description.
+pub fn documented(search_path: &[PathBuf], scan: bool) -> Vec<Documented> {
+    let listed = env::var(DOC_PLUGINS_VAR).unwrap_or_default();
+    let mut names: Vec<String> = listed
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_owned)
+        .collect();
+    if scan {
+        names.extend(discover(search_path).into_iter().map(|plugin| plugin.name));
+    }
+
+    let mut documented: Vec<Documented> = Vec::new();
+    for name in names {
+        if documented.iter().any(|d| d.name == name) {
+            continue;
+        }
+        let description = find(search_path, &name).and_then(|plugin| describe(&plugin));
+        documented.push(Documented { name, description });
+    }
+    documented
+}
+
+/// The one-line summary `mycli-<name> --plugin-describe` prints, or `None`
+/// when the plugin fails or prints nothing.
+pub fn describe(plugin: &Plugin) -> Option<String> {
+    let output = Command::new(&plugin.path)
+        .arg("--plugin-describe")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let text = String::from_utf8(output.stdout).ok()?;
+    text.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_owned)
+}
+
+fn plugin_name(path: &Path) -> Option<String> {
+    let file = path.file_name()?.to_str()?;
+    let name = file.strip_prefix(PREFIX)?;
+    let name = name.strip_suffix(env::consts::EXE_SUFFIX).unwrap_or(name);
+    (!name.is_empty() && !name.contains('.')).then(|| name.to_owned())
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
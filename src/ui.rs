@@ -0,0 +1,22 @@
+// Warnings, which `--strict` turns into errors.
+//
+// Commands report problems they can continue after through `ui::warn`, so
+// the strict contract lives in one place. Warnings go to the context's
+// stderr and, unlike the informational lines of `Output::Info`, are never
+// silenced by `--quiet`.
+
+use crate::error::AppError;
+use crate::i18n;
+use crate::Context;
+
+/// Report a problem the command can continue after: a warning on the
+/// stderr of `ctx`, or when `strict` an error for the caller to return.
+// Only `docs` warns so far, and it is part of the docs-gen feature.
+#[cfg_attr(not(feature = "docs-gen"), allow(dead_code))]
+pub fn warn(ctx: &mut Context, strict: bool, message: String) -> Result<(), AppError> {
+    if strict {
+        return Err(AppError::Strict(message));
+    }
+    let prefix = i18n::tr(ctx.lang, "warning.prefix");
+    writeln!(ctx.stderr, "{prefix}: {message}").map_err(AppError::Output)
+}
@@ -0,0 +1,188 @@
+// `mycli version`: detailed build information in a parser-friendly format.
+
+use serde_json::json;
+
+use crate::cli::VersionFormat;
+use crate::output::Style;
+
+/// Build details captured by build.rs.
+#[derive(Debug)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub git_hash: &'static str,
+    pub build_date: &'static str,
+    pub rustc: &'static str,
+    pub target: &'static str,
+    pub features: Vec<&'static str>,
+}
+
+impl BuildInfo {
+    /// Information about the running binary.
+    pub fn current() -> Self {
+        BuildInfo {
+            version: env!("CARGO_PKG_VERSION"),
+            git_hash: env!("MYCLI_GIT_HASH"),
+            build_date: env!("MYCLI_BUILD_DATE"),
+            rustc: env!("MYCLI_RUSTC"),
+            target: env!("MYCLI_TARGET"),
+            features: env!("MYCLI_FEATURES")
+                .split(',')
+                .filter(|f| !f.is_empty())
+                .collect(),
+        }
+    }
+
+    /// The `--version` output: `mycli <version>`, and with `verbose` the
+    /// features on a second line.
+    pub fn version_line(&self, verbose: bool) -> String {
+        let line = format!("mycli {}", self.version);
+        if !verbose {
+            return line;
+        }
+        match self.features.as_slice() {
+            [] => format!(
+                "{line}\n\
+                 features: (none)"
+            ),
+            features => format!(
+                "{line}\n\
+                 features: {}",
+                features.join(", ")
+            ),
+        }
+    }
+
+    /// Render in the requested format (the JSON shape is documented in the
+    /// long help of `mycli version`), JSON as `style` asks.
+    pub fn render(&self, format: VersionFormat, style: &Style) -> String {
+        match format {
+            VersionFormat::Plain => {
+                let features = match self.features.as_slice() {
+                    [] => "(none)".to_owned(),
+                    features => features.join(", "),
+                };
+                format!(
+                    "mycli {}\n\
+                     git hash:   {}\n\
+                     build date: {}\n\
+                     rustc:      {}\n\
+                     target:     {}\n\
+                     features:   {features}",
+                    self.version, self.git_hash, self.build_date, self.rustc, self.target,
+                )
+            }
+            VersionFormat::Json => {
+                let info = json!({
+                    "version": self.version,
+                    "git_hash": self.git_hash,
+                    "build_date": self.build_date,
+                    "rustc": self.rustc,
+                    "target": self.target,
+                    "features": self.features,
+                });
+                style.json(&info)
+            }
+            VersionFormat::Toml => {
+                let features: Vec<String> = self.features.iter().map(|f| toml_string(f)).collect();
+                format!(
+                    "version = {}\n\
+                     git_hash = {}\n\
+                     build_date = {}\n\
+                     rustc = {}\n\
+                     target = {}\n\
+                     features = [{}]",
+                    toml_string(self.version),
+                    toml_string(self.git_hash),
+                    toml_string(self.build_date),
+                    toml_string(self.rustc),
+                    toml_string(self.target),
+                    features.join(", "),
+                )
+            }
+        }
+    }
+}
+
+// TOML basic strings share JSON's escaping rules for the characters we emit.
+fn toml_string(value: &str) -> String {
+    serde_json::to_string(value).expect("strings serialize")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(features: Vec<&'static str>) -> BuildInfo {
+        BuildInfo {
+            version: "1.2.3",
+            git_hash: "0123abc",
+            build_date: "2024-05-01",
+            rustc: "rustc 1.80.0 (051478957 2024-07-21)",
+            target: "x86_64-unknown-linux-gnu",
+            features,
+        }
+    }
+
+    #[test]
+    fn plain() {
+        assert_eq!(
+            info(vec!["docs-gen"]).render(VersionFormat::Plain, &Style::default()),
+            "mycli 1.2.3\n\
+             git hash:   0123abc\n\
+             build date: 2024-05-01\n\
+             rustc:      rustc 1.80.0 (051478957 2024-07-21)\n\
+             target:     x86_64-unknown-linux-gnu\n\
+             features:   docs-gen"
+        );
+        assert!(info(vec![])
+            .render(VersionFormat::Plain, &Style::default())
+            .ends_with("features:   (none)"));
+    }
+
+    #[test]
+    fn version_line() {
+        assert_eq!(info(vec!["docs-gen"]).version_line(false), "mycli 1.2.3");
+        assert_eq!(
+            info(vec!["docs-gen", "x"]).version_line(true),
+            "mycli 1.2.3\nfeatures: docs-gen, x"
+        );
+        assert_eq!(
+            info(vec![]).version_line(true),
+            "mycli 1.2.3\nfeatures: (none)"
+        );
+    }
+
+    #[test]
+    fn json() {
+        let rendered = info(vec!["docs-gen"]).render(VersionFormat::Json, &Style::default());
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&rendered).unwrap(),
+            json!({
+                "version": "1.2.3",
+                "git_hash": "0123abc",
+                "build_date": "2024-05-01",
+                "rustc": "rustc 1.80.0 (051478957 2024-07-21)",
+                "target": "x86_64-unknown-linux-gnu",
+                "features": ["docs-gen"],
+            })
+        );
+    }
+
+    #[test]
+    fn toml() {
+        assert_eq!(
+            info(vec!["docs-gen", "x"]).render(VersionFormat::Toml, &Style::default()),
+            "version = \"1.2.3\"\n\
+             git_hash = \"0123abc\"\n\
+             build_date = \"2024-05-01\"\n\
+             rustc = \"rustc 1.80.0 (051478957 2024-07-21)\"\n\
+             target = \"x86_64-unknown-linux-gnu\"\n\
+             features = [\"docs-gen\", \"x\"]"
+        );
+    }
+
+    #[test]
+    fn default_is_not_a_feature() {
+        assert!(!BuildInfo::current().features.contains(&"default"));
+    }
+}
@@ -0,0 +1,121 @@
+// Parity of `man::render_all` (behind `cargo xtask mangen`, `mycli docs
+// --generate` and `--install`) with the pages the original build.rs wrote
+// through `clap_mangen::generate_to`, checked in under tests/golden/build-rs/.
+// Both must have the same pages, the same NAME and SYNOPSIS, and the same
+// options in OPTIONS; our descriptions of them, and the sections after
+// OPTIONS, are richer on purpose. After a change to the CLI, regenerate the
+// build.rs pages with
+//
+//     UPDATE_GOLDEN=1 cargo test --test build_rs_parity
+//
+// and review the diff like any other change.
+
+#![cfg(feature = "docs-gen")]
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Once;
+
+use clap::CommandFactory;
+use mycli::cli::Cli;
+use mycli::i18n::{self, Lang};
+use mycli::man;
+
+// The pages build.rs wrote, by file name.
+fn build_rs_pages() -> BTreeMap<String, String> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden/build-rs");
+    // Once, since the tests run in parallel.
+    static UPDATE: Once = Once::new();
+    UPDATE.call_once(|| {
+        if env::var_os("UPDATE_GOLDEN").is_some() {
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).expect("the fixture directory is writable");
+            let cmd = i18n::localize(Cli::command(), Lang::En);
+            clap_mangen::generate_to(cmd, &dir).expect("clap_mangen writes the pages");
+        }
+    });
+    fs::read_dir(&dir)
+        .expect("tests/golden/build-rs exists")
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            (name, fs::read_to_string(&path).unwrap())
+        })
+        .collect()
+}
+
+// The pages `man::render_all` renders, by file name.
+fn rendered_pages() -> BTreeMap<String, String> {
+    let cmd = i18n::localize(Cli::command(), Lang::En);
+    man::render_all(cmd, &man::Header::current())
+        .expect("pages render")
+        .into_iter()
+        .map(|page| (page.file_name, String::from_utf8(page.contents).unwrap()))
+        .collect()
+}
+
+// The lines of section `name`, heading included, up to the next heading.
+fn section<'a>(page: &'a str, name: &str) -> Vec<&'a str> {
+    let heading = format!(".SH {name}");
+    page.lines()
+        .skip_while(|line| *line != heading)
+        .enumerate()
+        .take_while(|(i, line)| *i == 0 || !line.starts_with(".SH "))
+        .map(|(_, line)| line)
+        .collect()
+}
+
+// The flags and value names of every entry in OPTIONS: the line after each
+// `.TP`, without the description that follows.
+fn options(page: &str) -> Vec<&str> {
+    let lines = section(page, "OPTIONS");
+    lines
+        .windows(2)
+        .filter(|pair| pair[0] == ".TP")
+        .map(|pair| pair[1])
+        .collect()
+}
+
+// `mycli-server.8` was `mycli-server.1` before the server page moved to the
+// administration section.
+fn stem(file_name: &str) -> &str {
+    file_name
+        .rsplit_once('.')
+        .map_or(file_name, |(stem, _)| stem)
+}
+
+#[test]
+fn same_pages_as_build_rs() {
+    let old: Vec<String> = build_rs_pages()
+        .keys()
+        .map(|n| stem(n).to_owned())
+        .collect();
+    let new: Vec<String> = rendered_pages()
+        .keys()
+        .map(|n| stem(n).to_owned())
+        .collect();
+    assert_eq!(new, old);
+}
+
+#[test]
+fn same_name_synopsis_and_options_as_build_rs() {
+    let old = build_rs_pages();
+    for (file_name, page) in rendered_pages() {
+        let (_, expected) = old
+            .iter()
+            .find(|(name, _)| stem(name) == stem(&file_name))
+            .unwrap_or_else(|| panic!("build.rs wrote no {file_name}"));
+        for name in ["NAME", "SYNOPSIS"] {
+            assert!(section(expected, name).len() > 1, "{name} of {file_name}");
+            assert_eq!(
+                section(&page, name),
+                section(expected, name),
+                "{name} of {file_name}"
+            );
+        }
+        assert!(!options(expected).is_empty(), "OPTIONS of {file_name}");
+        assert_eq!(options(&page), options(expected), "OPTIONS of {file_name}");
+    }
+}
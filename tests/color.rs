@@ -0,0 +1,38 @@
+// `--color` highlights JSON after serialization: the colored text minus its
+// escape codes is exactly the plain output.
+
+use std::process::Command;
+
+fn version_json(color: &str) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_mycli"))
+        .args(["version", "--format", "json", "--pretty", "--color", color])
+        .env_remove("NO_COLOR")
+        .output()
+        .expect("mycli runs");
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).expect("the output is UTF-8")
+}
+
+// Drop `ESC [ ... m` sequences.
+fn strip_codes(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('\x1b') {
+        out.push_str(&rest[..start]);
+        let end = rest[start..].find('m').expect("codes end with m");
+        rest = &rest[start + end + 1..];
+    }
+    out + rest
+}
+
+#[test]
+fn always_colors_pipes_and_only_adds_codes() {
+    let colored = version_json("always");
+    assert!(colored.contains("\x1b[1;34m\"version\"\x1b[0m"));
+    assert_eq!(strip_codes(&colored), version_json("never"));
+}
+
+#[test]
+fn auto_does_not_color_pipes() {
+    assert!(!version_json("auto").contains('\x1b'));
+}
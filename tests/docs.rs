@@ -0,0 +1,64 @@
+// `mycli docs --path` points at installed pages only.
+
+#![cfg(feature = "docs-gen")]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use serde_json::Value;
+
+fn mycli(args: &[&str], home: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_mycli"))
+        .args(args)
+        .env("HOME", home)
+        .env("MANPATH", home.join("man"))
+        .env_remove("MYCLI_LANG")
+        .output()
+        .expect("mycli runs")
+}
+
+#[test]
+fn path_of_missing_page_says_how_to_install() {
+    let home = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("docs-missing");
+    fs::create_dir_all(&home).unwrap();
+    let output = mycli(&["--errors", "json", "docs", "--path", "config.set"], &home);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    let report: Value = serde_json::from_slice(&output.stderr).expect("stderr is JSON");
+    assert_eq!(report["error"]["code"], "DOCS_NOT_INSTALLED");
+    assert!(
+        report["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("mycli-config-set.1"),
+        "{report}"
+    );
+    let hint = report["error"]["hint"].as_str().unwrap();
+    assert!(hint.contains("mycli docs --install"), "{hint}");
+}
+
+#[test]
+fn path_of_installed_page() {
+    let home = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("docs-installed");
+    let prefix = home.join("man");
+    let _ = fs::remove_dir_all(&home);
+    fs::create_dir_all(&home).unwrap();
+    let install = mycli(
+        &["docs", "--install", "--prefix", prefix.to_str().unwrap()],
+        &home,
+    );
+    assert!(install.status.success(), "{install:?}");
+
+    for (command, page) in [
+        ("config.set", "man1/mycli-config-set.1"),
+        ("server", "man8/mycli-server.8"),
+    ] {
+        let output = mycli(&["docs", "--path", command], &home);
+        assert!(output.status.success(), "{output:?}");
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            format!("{}\n", prefix.join(page).display())
+        );
+    }
+}
@@ -0,0 +1,32 @@
+// `mycli doctor --format ndjson` must stay parseable line by line, with the
+// records of `--format json` on the lines.
+
+use std::process::Command;
+
+use serde_json::Value;
+
+fn doctor(format: &str) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_mycli"))
+        .args(["doctor", "--pretty", "--format", format])
+        .env_remove("MYCLI_LANG")
+        .output()
+        .expect("mycli runs");
+    // Checks may fail on this machine; the report is printed either way.
+    String::from_utf8(output.stdout).expect("the report is UTF-8")
+}
+
+#[test]
+fn ndjson_lines_are_json_records() {
+    let lines: Vec<Value> = doctor("ndjson")
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("every line is a JSON document"))
+        .collect();
+
+    let report: Value = serde_json::from_str(&doctor("json")).expect("the report is JSON");
+    let checks = report["checks"].as_array().expect("checks is an array");
+    assert_eq!(lines.len(), checks.len());
+    for (line, check) in lines.iter().zip(checks) {
+        assert_eq!(line["name"], check["name"]);
+        assert_eq!(line["status"], check["status"]);
+    }
+}
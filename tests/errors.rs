@@ -0,0 +1,54 @@
+// `--errors json` reports for usage errors: one JSON object on stderr with
+// the whole message, and the help when clap would have shown it, whether
+// clap or mycli found the subcommand missing.
+
+use std::process::Command;
+
+use serde_json::Value;
+
+fn error(args: &[&str]) -> Value {
+    let output = Command::new(env!("CARGO_BIN_EXE_mycli"))
+        .arg("--errors=json")
+        .args(args)
+        .env_remove("MYCLI_LANG")
+        .output()
+        .expect("mycli runs");
+    assert_eq!(output.status.code(), Some(2));
+    let report: Value = serde_json::from_slice(&output.stderr).expect("stderr is one JSON object");
+    report["error"].clone()
+}
+
+#[test]
+fn missing_arguments_are_named() {
+    let error = error(&["config", "get"]);
+    assert_eq!(error["code"], "MISSING_ARGUMENT");
+    assert_eq!(
+        error["message"],
+        "the following required arguments were not provided: <KEY>"
+    );
+}
+
+#[test]
+fn missing_subcommand_keeps_the_help() {
+    let error = error(&["config"]);
+    assert_eq!(error["code"], "MISSING_COMMAND");
+    assert_eq!(error["message"], "a subcommand is required");
+    let help = error["help"].as_str().expect("the help is included");
+    assert!(help.contains("Usage: mycli config"), "{help}");
+    assert!(help.contains("Set a configuration value"), "{help}");
+}
+
+#[test]
+fn missing_subcommand_looks_the_same_at_the_root() {
+    let (root, config) = (error(&["--pretty"]), error(&["config"]));
+    let keys =
+        |error: &Value| -> Vec<String> { error.as_object().unwrap().keys().cloned().collect() };
+    assert_eq!(keys(&root), ["code", "help", "hint", "message"]);
+    assert_eq!(keys(&root), keys(&config));
+    assert_eq!(root["code"], config["code"]);
+    assert_eq!(root["message"], config["message"]);
+    assert!(root["help"]
+        .as_str()
+        .unwrap()
+        .contains("Usage: mycli [OPTIONS]"));
+}
@@ -0,0 +1,116 @@
+// Every example in the `after_help` of a command is run against the binary,
+// with HOME in a scratch directory holding the installed pages and a global
+// `core.editor` for `config get`, and a stub `mycli-hello` plugin on the
+// plugin path, and must not be a usage error.
+
+#![cfg(all(unix, feature = "docs-gen"))]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use clap::CommandFactory;
+use mycli::cli::Cli;
+use mycli::exit;
+use mycli::i18n::{self, Lang};
+use mycli::man;
+
+// Every example command line of `cmd` and its subcommands.
+fn examples(cmd: &clap::Command, out: &mut Vec<String>) {
+    if let Some(examples) = cmd
+        .get_after_help()
+        .and_then(|text| man::parse_examples(&text.to_string()))
+    {
+        out.extend(examples.into_iter().flat_map(|e| e.commands));
+    }
+    for sub in cmd.get_subcommands().filter(|s| !s.is_hide_set()) {
+        examples(sub, out);
+    }
+}
+
+// Shell words of `line` up to the first `|` or `&&`, with `'...'`/`"..."` quoting
+// and a leading `~/` expanded to `home`.
+fn words(line: &str, home: &Path) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote = None;
+    for c in line.chars() {
+        match (quote, c) {
+            (None, '|' | '&') => break,
+            (None, ' ') => words.extend(word.take()),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (Some(q), c) if c == q => quote = None,
+            (_, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    words
+        .into_iter()
+        .map(|w| match w.strip_prefix("~/") {
+            Some(rest) => home.join(rest).to_string_lossy().into_owned(),
+            None => w,
+        })
+        .collect()
+}
+
+#[test]
+fn documented_examples_run() {
+    let scratch = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("examples");
+    let (home, plugins) = (scratch.join("home"), scratch.join("plugins"));
+    fs::create_dir_all(&home).unwrap();
+    fs::create_dir_all(&plugins).unwrap();
+    let hello = plugins.join("mycli-hello");
+    fs::write(&hello, "#!/bin/sh\nexit 0\n").unwrap();
+    fs::set_permissions(&hello, fs::Permissions::from_mode(0o755)).unwrap();
+
+    // `docs --path` only finds installed pages, `config get` only values
+    // that are set.
+    for args in [
+        &["docs", "--install", "--user"][..],
+        &["config", "set", "--global", "core.editor", "vi"],
+    ] {
+        let setup = Command::new(env!("CARGO_BIN_EXE_mycli"))
+            .args(args)
+            .env("HOME", &home)
+            .env_remove("XDG_CONFIG_HOME")
+            .output()
+            .expect("mycli runs");
+        assert!(setup.status.success(), "{setup:?}");
+    }
+
+    let mut lines = Vec::new();
+    examples(&i18n::localize(Cli::command(), Lang::En), &mut lines);
+    assert!(lines.len() > 10, "examples are found: {lines:?}");
+
+    for line in lines {
+        let words = words(&line, &home);
+        assert_eq!(words[0], "mycli", "{line}");
+        let output = Command::new(env!("CARGO_BIN_EXE_mycli"))
+            .args(&words[1..])
+            .current_dir(&scratch)
+            .env("HOME", &home)
+            .env_remove("XDG_CONFIG_HOME")
+            .env("MYCLI_PLUGIN_PATH", &plugins)
+            .env("MYCLI_PAGER", "cat")
+            .env_remove("MYCLI_LANG")
+            .output()
+            .expect("mycli runs");
+        // doctor may find problems on this machine; anything else is a
+        // broken example.
+        assert_ne!(
+            output.status.code(),
+            Some(exit::USAGE),
+            "`{line}` is a usage error:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert!(
+            output.status.success() || words[1] == "doctor",
+            "`{line}` failed:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
@@ -0,0 +1,59 @@
+// Snapshots of `mycli --help` wrapped at 60 and 100 columns and unwrapped,
+// checked in under tests/golden/. Regenerate them after an intended change
+// with
+//
+//     UPDATE_GOLDEN=1 cargo test --test help_golden
+
+#![cfg(feature = "docs-gen")]
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use similar::TextDiff;
+
+fn check(width: &str, golden: &str) {
+    let output = Command::new(env!("CARGO_BIN_EXE_mycli"))
+        .args(["--help", "--help-width", width])
+        .env_remove("MYCLI_LANG")
+        .env_remove("MYCLI_HELP_WIDTH")
+        .output()
+        .expect("mycli runs");
+    assert!(output.status.success());
+    let actual = String::from_utf8(output.stdout).expect("help is UTF-8");
+
+    let golden = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(golden);
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&golden, &actual).expect("golden file is writable");
+        return;
+    }
+    let expected = fs::read_to_string(&golden).unwrap_or_default();
+    if actual != expected {
+        let diff = TextDiff::from_lines(&expected, &actual);
+        panic!(
+            "{} differs from the help output (UPDATE_GOLDEN=1 to accept):\n{}",
+            golden.display(),
+            diff.unified_diff()
+                .header("golden", "help")
+                .context_radius(3)
+        );
+    }
+}
+
+#[test]
+fn help_at_60_columns() {
+    check("60", "help-60.txt");
+}
+
+#[test]
+fn help_at_100_columns() {
+    check("100", "help-100.txt");
+}
+
+#[test]
+fn help_unwrapped() {
+    check("0", "help-unwrapped.txt");
+}
@@ -0,0 +1,53 @@
+// Hidden arguments and commands, like `--dump-cli-json`, stay out of the help
+// and the man pages. (Completions are checked in xtask, which generates them.)
+
+use clap::{Arg, ArgAction, Command, CommandFactory};
+use mycli::cli::Cli;
+use mycli::i18n::{self, Lang};
+
+// The CLI plus a hidden command and a hidden flag on `config get`.
+fn command() -> Command {
+    i18n::localize(Cli::command(), Lang::En)
+        .subcommand(Command::new("secret").about("Hidden command").hide(true))
+        .mut_subcommand("config", |config| {
+            config.mut_subcommand("get", |get| {
+                get.arg(
+                    Arg::new("secret_flag")
+                        .long("secret-flag")
+                        .action(ArgAction::SetTrue)
+                        .hide(true),
+                )
+            })
+        })
+}
+
+fn assert_hidden(text: &str, what: &str) {
+    for hidden in ["dump-cli-json", "dump\\-cli\\-json", "secret"] {
+        assert!(!text.contains(hidden), "{what} mentions {hidden}:\n{text}");
+    }
+}
+
+#[test]
+fn help_leaves_hidden_items_out() {
+    let mut cmd = command();
+    assert_hidden(&cmd.render_long_help().to_string(), "mycli --help");
+    assert_hidden(&cmd.render_help().to_string(), "mycli -h");
+    let get = cmd
+        .find_subcommand_mut("config")
+        .and_then(|config| config.find_subcommand_mut("get"))
+        .unwrap();
+    assert_hidden(
+        &get.render_long_help().to_string(),
+        "mycli config get --help",
+    );
+}
+
+#[cfg(feature = "docs-gen")]
+#[test]
+fn man_pages_leave_hidden_items_out() {
+    let pages = mycli::man::render_all(command(), &mycli::man::Header::current()).unwrap();
+    assert!(pages.iter().all(|page| !page.file_name.contains("secret")));
+    for page in pages {
+        assert_hidden(&String::from_utf8(page.contents).unwrap(), &page.file_name);
+    }
+}
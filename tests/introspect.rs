@@ -0,0 +1,47 @@
+// The schema file written by `cargo xtask mangen` and the runtime
+// `--dump-cli-json` output come from the same serializer; check that they
+// are byte for byte the same for the current CLI.
+
+use std::process::Command;
+
+use clap::CommandFactory;
+use mycli::cli::Cli;
+use mycli::introspect;
+
+#[test]
+fn dump_matches_schema_file() {
+    let output = Command::new(env!("CARGO_BIN_EXE_mycli"))
+        .arg("--dump-cli-json")
+        .env_remove("MYCLI_LANG")
+        .output()
+        .expect("mycli runs");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).expect("the dump is UTF-8"),
+        introspect::to_json(Cli::command())
+    );
+}
+
+#[test]
+fn args_have_tree_wide_anchors() {
+    let dump = introspect::dump(Cli::command());
+    let config = &dump["command"]["subcommands"]
+        .as_array()
+        .expect("subcommands is an array")
+        .iter()
+        .find(|c| c["name"] == "config")
+        .expect("config is dumped")["subcommands"];
+    let set = config
+        .as_array()
+        .expect("subcommands is an array")
+        .iter()
+        .find(|c| c["name"] == "set")
+        .expect("config set is dumped");
+    let global = set["args"]
+        .as_array()
+        .expect("args is an array")
+        .iter()
+        .find(|a| a["id"] == "global")
+        .expect("--global is dumped");
+    assert_eq!(global["anchor"], "config.set.--global");
+}
@@ -0,0 +1,83 @@
+// Golden-file tests for the generated man pages: the roff of a few pages is
+// compared with the copies checked in under tests/golden/. After an
+// intended change to the CLI or the page layout, regenerate them with
+//
+//     UPDATE_GOLDEN=1 cargo test --test man_golden
+//
+// and review the diff like any other change.
+
+#![cfg(feature = "docs-gen")]
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use clap::CommandFactory;
+use mycli::cli::Cli;
+use mycli::i18n::{self, Lang};
+use mycli::man;
+use similar::TextDiff;
+
+// The `.TH` date comes from git or SOURCE_DATE_EPOCH at build time; every
+// other part of the page only depends on the source.
+fn normalize(page: &str) -> String {
+    page.lines()
+        .map(|line| match line.strip_prefix(".TH ") {
+            Some(args) => {
+                let mut args: Vec<&str> = args.splitn(4, ' ').collect();
+                if args.len() > 2 {
+                    args[2] = "DATE";
+                }
+                format!(".TH {}", args.join(" "))
+            }
+            None => line.to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        + "\n"
+}
+
+fn check(path: &[&str]) {
+    let cmd = i18n::localize(Cli::command(), Lang::En);
+    let page = man::render_path(cmd, path, &man::Header::current()).expect("page renders");
+    let actual = normalize(&String::from_utf8(page).expect("pages are UTF-8"));
+
+    let golden = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(man::file_name(path));
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&golden, &actual).expect("golden file is writable");
+        return;
+    }
+    let expected = fs::read_to_string(&golden).unwrap_or_default();
+    if actual != expected {
+        let diff = TextDiff::from_lines(&expected, &actual);
+        panic!(
+            "{} differs from the rendered page (UPDATE_GOLDEN=1 to accept):\n{}",
+            golden.display(),
+            diff.unified_diff()
+                .header("golden", "rendered")
+                .context_radius(3)
+        );
+    }
+}
+
+#[test]
+fn root_page() {
+    check(&["mycli"]);
+}
+
+#[test]
+fn config_get_page() {
+    check(&["mycli", "config", "get"]);
+}
+
+#[test]
+fn config_set_page() {
+    check(&["mycli", "config", "set"]);
+}
+
+#[test]
+fn server_page() {
+    check(&["mycli", "server"]);
+}
@@ -0,0 +1,94 @@
+// The set of pages `man::generate_all` writes for the current CLI, shared by
+// `cargo xtask mangen`, `mycli docs --generate` and `--install`, what they
+// say about option defaults, and how examples are escaped.
+
+#![cfg(feature = "docs-gen")]
+
+use std::fs;
+use std::path::PathBuf;
+
+use clap::CommandFactory;
+use mycli::cli::Cli;
+use mycli::i18n::{self, Lang};
+use mycli::man;
+
+#[test]
+fn one_page_per_command_with_dash_joined_names() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("man-pages");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let cmd = i18n::localize(Cli::command(), Lang::En);
+    let pages = man::generate_all(cmd, &man::Header::current(), &dir).unwrap();
+
+    let names: Vec<String> = pages
+        .iter()
+        .map(|page| {
+            let name = page
+                .path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned();
+            assert!(name.ends_with(&format!(".{}", page.section)), "{name}");
+            name
+        })
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "mycli.1",
+            "mycli-config.1",
+            "mycli-config-get.1",
+            "mycli-config-set.1",
+            "mycli-server.8",
+            "mycli-remote.1",
+            "mycli-plugins.1",
+            "mycli-plugins-list.1",
+            "mycli-doctor.1",
+            "mycli-version.1",
+            "mycli-docs.1",
+        ]
+    );
+
+    let mut on_disk: Vec<String> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    on_disk.sort();
+    let mut expected = names.clone();
+    expected.sort();
+    assert_eq!(on_disk, expected);
+}
+
+#[test]
+fn server_page_documents_defaults() {
+    let cmd = i18n::localize(Cli::command(), Lang::En);
+    let page = man::render_path(cmd, &["mycli", "server"], &man::Header::current()).unwrap();
+    let page = String::from_utf8(page).unwrap();
+    for option in [
+        r"\fB\-\-port\fR \fI<PORT>\fR [default: 8080]",
+        r"\fB\-\-addr\fR \fI<ADDR>\fR [default: 127.0.0.1]",
+        r"conflicts with \-\-quiet [may be repeated]",
+    ] {
+        assert!(page.contains(option), "{option} is missing:\n{page}");
+    }
+}
+
+#[test]
+fn config_set_examples_are_escaped() {
+    let cmd = i18n::localize(Cli::command(), Lang::En);
+    let path = ["mycli", "config", "set"];
+    let page = man::render_path(cmd, &path, &man::Header::current()).unwrap();
+    let page = String::from_utf8(page).unwrap();
+    let examples = &page[page.find(".SH EXAMPLES\n").expect("an EXAMPLES section")..];
+    let example = "\
+.TP
+Set a value for every repository
+.RS 4
+.nf
+mycli config set \\-\\-global user.name \"Jane Doe\"
+.fi
+.RE
+";
+    assert!(examples.contains(example), "{examples}");
+}
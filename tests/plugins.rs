@@ -0,0 +1,123 @@
+// Unknown subcommands run `mycli-<name>` from MYCLI_PLUGIN_PATH with the
+// arguments clap collected after the name, and the parsed global output
+// flags; `plugins list --null` prints them as NUL-ended records.
+
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::process::Command;
+
+// Runs `mycli <args>` with a `mycli-echo` plugin that prints MYCLI_QUIET and
+// its arguments, one per line.
+fn run(args: &[&str]) -> (Option<i32>, String, String) {
+    let plugins = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("plugins");
+    fs::create_dir_all(&plugins).unwrap();
+    let echo = plugins.join("mycli-echo");
+    fs::write(
+        &echo,
+        "#!/bin/sh\necho \"quiet=$MYCLI_QUIET\"\nfor a in \"$@\"; do echo \"$a\"; done\n",
+    )
+    .unwrap();
+    fs::set_permissions(&echo, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_mycli"))
+        .args(args)
+        .env("MYCLI_PLUGIN_PATH", &plugins)
+        .env_remove("MYCLI_LANG")
+        .env_remove("MYCLI_QUIET")
+        .env_remove("NO_COLOR")
+        .output()
+        .expect("mycli runs");
+    (
+        output.status.code(),
+        String::from_utf8(output.stdout).expect("stdout is UTF-8"),
+        String::from_utf8(output.stderr).expect("stderr is UTF-8"),
+    )
+}
+
+#[test]
+fn plugin_gets_everything_after_its_name() {
+    let (code, stdout, _) = run(&["echo", "echo", "--x"]);
+    assert_eq!(code, Some(0));
+    assert_eq!(stdout, "quiet=0\necho\n--x\n");
+}
+
+#[test]
+fn plugin_sees_the_parsed_quiet_flag() {
+    for args in [
+        &["-q", "echo"][..],
+        &["--quiet", "echo"],
+        &["-q", "--pretty", "echo"],
+    ] {
+        let (code, stdout, stderr) = run(args);
+        assert_eq!(code, Some(0), "{args:?}: {stderr}");
+        assert_eq!(stdout, "quiet=1\n", "{args:?}");
+    }
+    let (_, stdout, _) = run(&["echo", "-q"]);
+    assert_eq!(stdout, "quiet=0\n-q\n");
+}
+
+#[test]
+fn plugin_sees_the_output_flags() {
+    let plugins = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("plugins-env");
+    fs::create_dir_all(&plugins).unwrap();
+    let env = plugins.join("mycli-env");
+    fs::write(
+        &env,
+        "#!/bin/sh\necho \"$MYCLI_QUIET $MYCLI_PRETTY $MYCLI_COLOR $MYCLI_ERRORS\"\n",
+    )
+    .unwrap();
+    fs::set_permissions(&env, fs::Permissions::from_mode(0o755)).unwrap();
+
+    for (args, expected) in [
+        (&["env"][..], "0 0 never human\n"),
+        (
+            &["--pretty", "--color", "always", "--errors", "json", "env"],
+            "0 1 always json\n",
+        ),
+        // stdout is a pipe here, so `auto` means no color.
+        (&["-q", "--color", "auto", "env"], "1 0 never human\n"),
+    ] {
+        let output = Command::new(env!("CARGO_BIN_EXE_mycli"))
+            .args(args)
+            .env("MYCLI_PLUGIN_PATH", &plugins)
+            .output()
+            .expect("mycli runs");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            expected,
+            "{args:?}"
+        );
+    }
+}
+
+#[test]
+fn unknown_command_is_a_usage_error() {
+    let (code, _, stderr) = run(&["--errors", "json", "confg"]);
+    assert_eq!(code, Some(2));
+    assert!(stderr.contains("\"UNKNOWN_COMMAND\""), "{stderr}");
+    assert!(stderr.contains("'config'"), "{stderr}");
+}
+
+#[test]
+fn list_null_keeps_paths_with_newlines_intact() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("plugins-null/two\nlines");
+    fs::create_dir_all(&dir).unwrap();
+    let plugin = dir.join("mycli-odd");
+    fs::write(&plugin, "#!/bin/sh\n").unwrap();
+    fs::set_permissions(&plugin, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_mycli"))
+        .args(["plugins", "list", "--null"])
+        .env("MYCLI_PLUGIN_PATH", &dir)
+        .env("PATH", "")
+        .output()
+        .expect("mycli runs");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("odd\t{}\0", plugin.display())
+    );
+}
@@ -0,0 +1,33 @@
+// `--quiet` and `server --verbose` conflict wherever the global `-q` is
+// given.
+
+use std::process::Command;
+
+fn conflict(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_mycli"))
+        .args(["--errors", "json"])
+        .args(args)
+        .env_remove("MYCLI_LANG")
+        .output()
+        .expect("mycli runs");
+    assert_eq!(output.status.code(), Some(2), "{args:?}");
+    String::from_utf8(output.stderr).expect("stderr is UTF-8")
+}
+
+#[test]
+fn quiet_conflicts_with_verbose_before_the_subcommand() {
+    let before = conflict(&["-q", "server", "-v"]);
+    assert!(before.contains("\"ARGUMENT_CONFLICT\""), "{before}");
+    assert_eq!(before, conflict(&["server", "-q", "-v"]));
+    assert_eq!(before, conflict(&["--quiet", "server", "-vv"]));
+}
+
+#[test]
+fn quiet_alone_is_fine() {
+    let output = Command::new(env!("CARGO_BIN_EXE_mycli"))
+        .args(["-q", "server"])
+        .output()
+        .expect("mycli runs");
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
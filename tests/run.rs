@@ -0,0 +1,164 @@
+// `mycli::run` in this process, against a `Context` of temporary directories
+// with the output captured in memory: results come back as an `Output` or an
+// `AppError`, never as an exit, and the process environment is left alone.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use mycli::cli::Cli;
+use mycli::config::Scope;
+use mycli::error::AppError;
+use mycli::i18n::Lang;
+use mycli::output::Style;
+use mycli::{exit, Context, Output};
+
+// An empty config root, working directory and plugin directory for `test`.
+fn dirs(test: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+        .join("run")
+        .join(test);
+    let _ = fs::remove_dir_all(&dir);
+    for sub in ["config", "work", "plugins"] {
+        fs::create_dir_all(dir.join(sub)).unwrap();
+    }
+    dir
+}
+
+// Runs `mycli <args>` in the directories of `dir`, off a terminal, returning
+// the outcome and stdout with the output rendered into it.
+fn run(dir: &Path, args: &[&str]) -> (Result<Output, AppError>, String) {
+    let cli = Cli::try_parse_from(["mycli"].iter().chain(args)).expect("the arguments parse");
+    let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+    let mut ctx = Context {
+        stdout: &mut stdout,
+        stderr: &mut stderr,
+        interactive: false,
+        lang: Lang::En,
+        config_root: Some(dir.join("config")),
+        work_dir: dir.join("work"),
+        plugin_path: vec![dir.join("plugins")],
+    };
+    let style = Style::new(&cli, &ctx);
+    let result = mycli::run(cli, &mut ctx);
+    if let Ok(output) = &result {
+        output
+            .render(&mut stdout, &style)
+            .expect("memory takes the output");
+    }
+    (result, String::from_utf8(stdout).expect("stdout is UTF-8"))
+}
+
+#[test]
+fn config_set_and_get_round_trip() {
+    let dir = dirs("round-trip");
+
+    let (result, stdout) = run(&dir, &["config", "set", "user.name", "Ada"]);
+    assert!(matches!(result.unwrap(), Output::Info(_)));
+    let local = dir.join("work/.mycli/config.json");
+    assert_eq!(
+        stdout,
+        format!("set user.name to Ada in {}\n", local.display())
+    );
+
+    let (result, stdout) = run(&dir, &["config", "get", "user.name"]);
+    match result.unwrap() {
+        Output::Value { entry, .. } => {
+            assert_eq!(entry.scope, Scope::Local);
+            assert_eq!(entry.path, local);
+        }
+        output => panic!("not a value: {output:?}"),
+    }
+    assert_eq!(stdout, "Ada\n");
+
+    let (result, stdout) = run(
+        &dir,
+        &["-q", "config", "set", "core.editor", "vim", "--global"],
+    );
+    result.unwrap();
+    assert_eq!(stdout, "");
+    assert!(dir.join("config/config.json").is_file());
+
+    let (_, stdout) = run(&dir, &["config", "get", "core.editor", "--format", "json"]);
+    assert_eq!(
+        stdout,
+        "{\"key\":\"core.editor\",\"scope\":\"global\",\"value\":\"vim\"}\n"
+    );
+}
+
+#[test]
+fn local_values_win_over_global_ones() {
+    let dir = dirs("scopes");
+    run(&dir, &["config", "set", "user.name", "Grace", "--global"])
+        .0
+        .unwrap();
+    run(&dir, &["config", "set", "user.name", "Ada"]).0.unwrap();
+    run(&dir, &["config", "set", "user.name", "Edsger", "--global"])
+        .0
+        .unwrap();
+
+    let (_, stdout) = run(&dir, &["config", "get", "user.name"]);
+    assert_eq!(stdout, "Ada\n");
+}
+
+#[test]
+fn unset_keys_and_broken_files_are_errors() {
+    let dir = dirs("errors");
+    let (result, stdout) = run(&dir, &["config", "get", "user.name"]);
+    let err = result.expect_err("nothing is set");
+    assert_eq!(err.code(), "KEY_NOT_FOUND");
+    assert_eq!(err.exit_code(), exit::FAILURE);
+    assert_eq!(stdout, "");
+
+    fs::write(dir.join("config/config.json"), "[]").unwrap();
+    let (result, _) = run(&dir, &["config", "set", "user.name", "Ada", "--global"]);
+    assert_eq!(result.expect_err("not an object").code(), "CONFIG_FAILED");
+}
+
+#[test]
+fn usage_errors_are_returned() {
+    let dir = dirs("usage");
+    for (args, code) in [
+        (&["--pretty"][..], "MISSING_COMMAND"),
+        (&["-q", "server", "-v"], "ARGUMENT_CONFLICT"),
+    ] {
+        let (result, stdout) = run(&dir, args);
+        let err = result.expect_err("a usage error");
+        assert!(matches!(err, AppError::Usage(_)), "{args:?}: {err:?}");
+        assert_eq!(err.code(), code, "{args:?}");
+        assert_eq!(stdout, "", "{args:?}");
+    }
+}
+
+#[test]
+fn version_and_doctor_render_their_results() {
+    let dir = dirs("version-doctor");
+    let (result, stdout) = run(&dir, &["--version"]);
+    assert_eq!(result.unwrap().status().unwrap(), exit::SUCCESS);
+    assert_eq!(stdout, format!("mycli {}\n", env!("CARGO_PKG_VERSION")));
+
+    let (result, stdout) = run(&dir, &["doctor", "--format", "json"]);
+    let Output::Report(report) = result.unwrap() else {
+        panic!("doctor returns its report");
+    };
+    let rendered: serde_json::Value = serde_json::from_str(&stdout).expect("doctor prints JSON");
+    assert_eq!(
+        rendered["checks"].as_array().map(Vec::len),
+        Some(report.results.len())
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn plugin_output_passes_through_the_context() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = dirs("plugins");
+    let plugin = dir.join("plugins/mycli-fail");
+    fs::write(&plugin, "#!/bin/sh\necho \"args: $*\"\nexit 3\n").unwrap();
+    fs::set_permissions(&plugin, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let (result, stdout) = run(&dir, &["fail", "a", "b"]);
+    assert_eq!(result.unwrap().status().unwrap(), 3);
+    assert_eq!(stdout, "args: a b\n");
+}
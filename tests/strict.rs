@@ -0,0 +1,42 @@
+// `--strict` turns the warning about an undescribed MYCLI_DOC_PLUGINS entry
+// into an error; without it the pages are written anyway.
+
+#![cfg(feature = "docs-gen")]
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn markdown(name: &str, extra: &[&str]) -> (Output, PathBuf) {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let output = Command::new(env!("CARGO_BIN_EXE_mycli"))
+        .args(["docs", "--quiet", "--errors", "json", "--markdown"])
+        .arg(&dir)
+        .args(extra)
+        .env("MYCLI_DOC_PLUGINS", "no-such-plugin")
+        .env_remove("MYCLI_LANG")
+        .output()
+        .expect("mycli runs");
+    (output, dir)
+}
+
+#[test]
+fn undescribed_plugin_is_a_warning() {
+    let (output, dir) = markdown("strict-off", &[]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.starts_with("warning: plugin 'no-such-plugin'"),
+        "{stderr}"
+    );
+    assert!(dir.join("mycli.md").is_file());
+}
+
+#[test]
+fn undescribed_plugin_fails_with_strict() {
+    let (output, dir) = markdown("strict-on", &["--strict"]);
+    assert_eq!(output.status.code(), Some(1));
+    let error: serde_json::Value =
+        serde_json::from_slice(&output.stderr).expect("--errors json prints JSON");
+    assert_eq!(error["error"]["code"], "STRICT_WARNING");
+    assert!(!dir.join("mycli.md").exists());
+}
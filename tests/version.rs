@@ -0,0 +1,43 @@
+// `--version` prints one line; `--verbose` adds the compiled-in features.
+
+use std::process::Command;
+
+fn mycli(args: &[&str]) -> (Option<i32>, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_mycli"))
+        .args(args)
+        .output()
+        .expect("mycli runs");
+    (
+        output.status.code(),
+        String::from_utf8(output.stdout).expect("stdout is UTF-8"),
+    )
+}
+
+#[test]
+fn version_is_one_line() {
+    let expected = format!("mycli {}\n", env!("CARGO_PKG_VERSION"));
+    assert_eq!(mycli(&["--version"]), (Some(0), expected.clone()));
+    assert_eq!(mycli(&["-V"]), (Some(0), expected));
+}
+
+#[test]
+fn verbose_version_lists_features() {
+    let (code, stdout) = mycli(&["--version", "--verbose"]);
+    assert_eq!(code, Some(0));
+    let features = stdout
+        .lines()
+        .nth(1)
+        .and_then(|line| line.strip_prefix("features: "))
+        .expect("a features line");
+    let expected = if cfg!(feature = "docs-gen") {
+        "docs-gen"
+    } else {
+        "(none)"
+    };
+    assert_eq!(features, expected);
+}
+
+#[test]
+fn verbose_needs_version() {
+    assert_eq!(mycli(&["--verbose"]).0, Some(2));
+}
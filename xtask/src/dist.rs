@@ -0,0 +1,232 @@
+// `cargo xtask dist`: a release archive with the binary and its docs.
+//
+// The tree is laid out like an install prefix, so unpacking it into
+// /usr/local (or `--strip-components=1` into any prefix) just works:
+//
+//   mycli-<version>-<target>/
+//     bin/mycli
+//     share/man/man1/mycli.1, share/man/man8/mycli-server.8, share/man/de/...
+//     share/completions/mycli.bash, _mycli, ...
+//     LICENSE*, if the workspace has any
+//
+// Entries are sorted and carry fixed owners and mtimes (SOURCE_DATE_EPOCH,
+// else 0), so the same binary always gives the same archive.
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use clap::{CommandFactory, ValueEnum};
+use flate2::{Compression, GzBuilder};
+use mycli::cli::Cli;
+use mycli::i18n::{self, Lang};
+use mycli::man;
+use mycli::version::BuildInfo;
+use sha2::{Digest, Sha256};
+use zip::write::SimpleFileOptions;
+
+use crate::{completions, source_date_epoch, target_dir, workspace_dir, CompletionShell};
+
+/// Build the release binary for `target` (default: the host), assemble the
+/// tree next to it and pack it. Returns the archives written.
+pub fn dist(target: Option<&str>) -> io::Result<Vec<PathBuf>> {
+    let info = BuildInfo::current();
+    let triple = target.unwrap_or(info.target);
+    let windows = triple.contains("windows");
+    let binary = build_release(target, windows)?;
+
+    let name = format!("mycli-{}-{triple}", info.version);
+    let out = target_dir().join("dist");
+    let root = out.join(&name);
+    match fs::remove_dir_all(&root) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+
+    let bin = root.join("bin");
+    fs::create_dir_all(&bin)?;
+    fs::copy(
+        &binary,
+        bin.join(binary.file_name().expect("binary has a name")),
+    )?;
+    man_pages(&root.join("share").join("man"))?;
+    completions(
+        &root.join("share").join("completions"),
+        CompletionShell::value_variants(),
+    )?;
+    for entry in fs::read_dir(workspace_dir())? {
+        let entry = entry?;
+        if entry.file_name().to_string_lossy().starts_with("LICENSE") {
+            fs::copy(entry.path(), root.join(entry.file_name()))?;
+        }
+    }
+
+    let entries = walk(&root)?;
+    let mtime = source_date_epoch();
+    let mut archives = vec![out.join(format!("{name}.tar.gz"))];
+    write_tar_gz(&archives[0], &name, &root, &entries, mtime)?;
+    if windows {
+        archives.push(out.join(format!("{name}.zip")));
+        write_zip(&archives[1], &name, &root, &entries)?;
+    }
+    Ok(archives)
+}
+
+/// Hex SHA-256 of `file`, as `sha256sum` prints it.
+pub fn sha256(file: &Path) -> io::Result<String> {
+    Ok(format!("{:x}", Sha256::digest(fs::read(file)?)))
+}
+
+// `cargo build --release` of the mycli binary; with `target`, the artifact
+// is looked up where cargo puts cross-compiled ones.
+fn build_release(target: Option<&str>, windows: bool) -> io::Result<PathBuf> {
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let mut build = Command::new(cargo);
+    build.args(["build", "--release", "--package", "mycli", "--bin", "mycli"]);
+    build.args(target.map(|t| ["--target", t]).into_iter().flatten());
+    let status = build.current_dir(workspace_dir()).status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!("cargo build failed ({status})")));
+    }
+
+    let mut dir = target_dir();
+    dir.extend(target);
+    let file = if windows { "mycli.exe" } else { "mycli" };
+    Ok(dir.join("release").join(file))
+}
+
+// Every page in every language under `share_man`, in the `man<section>`
+// directories man(1) searches.
+fn man_pages(share_man: &Path) -> io::Result<()> {
+    let header = man::Header::current();
+    for lang in Lang::ALL {
+        let mut dest = share_man.to_owned();
+        dest.extend(man::lang_dir(lang));
+        let cmd = i18n::localize(Cli::command(), lang);
+        for page in man::render_all(cmd, &header)? {
+            let dir = dest.join(format!("man{}", page.section));
+            fs::create_dir_all(&dir)?;
+            fs::write(dir.join(&page.file_name), page.contents)?;
+        }
+    }
+    Ok(())
+}
+
+// Paths below `root`, relative to it, parents before children and sorted
+// byte-wise so the archive order does not depend on the file system.
+fn walk(root: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut entries = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(root.join(&dir))? {
+            let entry = entry?;
+            let path = dir.join(entry.file_name());
+            if entry.file_type()?.is_dir() {
+                pending.push(path.clone());
+            }
+            entries.push(path);
+        }
+    }
+    entries.sort();
+    Ok(entries)
+}
+
+// Directories and the binary are executable, everything else is not.
+fn mode(root: &Path, entry: &Path) -> u32 {
+    if root.join(entry).is_dir() || entry.starts_with("bin") {
+        0o755
+    } else {
+        0o644
+    }
+}
+
+fn write_tar_gz(
+    file: &Path,
+    name: &str,
+    root: &Path,
+    entries: &[PathBuf],
+    mtime: u64,
+) -> io::Result<()> {
+    let gz = GzBuilder::new()
+        .mtime(u32::try_from(mtime).unwrap_or(u32::MAX))
+        .write(File::create(file)?, Compression::best());
+    let mut tar = tar::Builder::new(gz);
+    for entry in entries {
+        let path = root.join(entry);
+        let mut header = tar::Header::new_gnu();
+        header.set_mode(mode(root, entry));
+        header.set_mtime(mtime);
+        header.set_uid(0);
+        header.set_gid(0);
+        let name = Path::new(name).join(entry);
+        if path.is_dir() {
+            header.set_entry_type(tar::EntryType::Directory);
+            header.set_size(0);
+            tar.append_data(&mut header, name, io::empty())?;
+        } else {
+            let contents = fs::read(&path)?;
+            header.set_size(contents.len() as u64);
+            tar.append_data(&mut header, name, contents.as_slice())?;
+        }
+    }
+    tar.into_inner()?.finish()?.flush()
+}
+
+// Zip entries keep the 1980-01-01 DOS epoch: the format cannot store 1970.
+fn write_zip(file: &Path, name: &str, root: &Path, entries: &[PathBuf]) -> io::Result<()> {
+    let mut zip = zip::ZipWriter::new(File::create(file)?);
+    for entry in entries {
+        let path = root.join(entry);
+        let options = SimpleFileOptions::default()
+            .last_modified_time(zip::DateTime::default())
+            .unix_permissions(mode(root, entry));
+        // Zip names use `/` on every platform.
+        let name = Path::new(name)
+            .join(entry)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        if path.is_dir() {
+            zip.add_directory(name, options)?;
+        } else {
+            zip.start_file(name, options)?;
+            zip.write_all(&fs::read(&path)?)?;
+        }
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn man_pages_go_straight_into_section_directories() {
+        let share_man = target_dir().join("tmp/xtask-dist-man");
+        let _ = fs::remove_dir_all(&share_man);
+        man_pages(&share_man).unwrap();
+
+        let entries = walk(&share_man).unwrap();
+        let dirs: Vec<_> = entries
+            .iter()
+            .filter(|e| share_man.join(e).is_dir())
+            .collect();
+        assert_eq!(
+            dirs,
+            ["de", "de/man1", "de/man8", "man1", "man8"].map(Path::new)
+        );
+        for page in [
+            "man1/mycli.1",
+            "man8/mycli-server.8",
+            "de/man1/mycli-config-set.1",
+        ] {
+            assert!(
+                entries.contains(&PathBuf::from(page)),
+                "{page}: {entries:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,584 @@
+// Project automation, run as `cargo xtask <task>` (see .cargo/config.toml).
+//
+// Documentation is generated here rather than in build.rs so normal builds
+// stay fast and quiet. Everything comes from the library's `Cli::command()`,
+// the same definition the binary parses with.
+
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use clap_complete_fig::Fig;
+use clap_complete_nushell::Nushell;
+use mycli::cli::Cli;
+use mycli::i18n::{self, Lang};
+use mycli::{introspect, man, plugins};
+
+mod dist;
+
+#[derive(Debug, Parser)]
+#[command(name = "xtask", about = "Development tasks for mycli")]
+struct Xtask {
+    #[command(subcommand)]
+    task: Task,
+}
+
+#[derive(Debug, Subcommand)]
+enum Task {
+    /// Generate a man page for every command
+    Mangen {
+        /// Output directory (default: target/man)
+        #[arg(long, value_name = "DIR")]
+        out: Option<PathBuf>,
+
+        /// Also write gzipped pages (`mycli.1.gz`, ...) as distros ship them
+        #[arg(long)]
+        gzip: bool,
+
+        /// List the `mycli-<name>` plugins found on this machine in the root
+        /// page, besides those named in MYCLI_DOC_PLUGINS
+        #[arg(long)]
+        plugins: bool,
+
+        /// Write one consolidated mycli.1 with every command as a subsection
+        #[arg(long)]
+        single: bool,
+
+        /// Regenerate even if the inputs match the last run
+        #[arg(long)]
+        force: bool,
+
+        /// `.TH` source field (default: MYCLI_MAN_SOURCE at build time, else "mycli <version>")
+        #[arg(long, value_name = "TEXT")]
+        source: Option<String>,
+
+        /// `.TH` manual field (default: MYCLI_MAN_MANUAL at build time, else "Mycli Manual")
+        #[arg(long, value_name = "TEXT")]
+        manual: Option<String>,
+    },
+    /// Generate shell completion scripts
+    Completions {
+        /// Output directory (default: target/completions)
+        #[arg(long, value_name = "DIR")]
+        out: Option<PathBuf>,
+
+        /// Only generate for SHELL (repeatable; default: all)
+        #[arg(long, value_name = "SHELL")]
+        shell: Vec<CompletionShell>,
+    },
+    /// Build a release archive with the binary, man pages and completions
+    Dist {
+        /// Target triple to build for (default: the host)
+        #[arg(long, value_name = "TRIPLE")]
+        target: Option<String>,
+    },
+}
+
+/// Shells we write completions for: clap_complete's own and the ones served
+/// by its extension crates.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    #[value(name = "powershell")]
+    PowerShell,
+    Elvish,
+    Nushell,
+    Fig,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    match Xtask::parse().task {
+        Task::Mangen {
+            out,
+            gzip,
+            plugins,
+            single,
+            force,
+            source,
+            manual,
+        } => {
+            let dir = out.unwrap_or_else(|| target_dir().join("man"));
+            let defaults = man::Header::current();
+            let header = man::Header {
+                source: source.unwrap_or(defaults.source),
+                manual: manual.unwrap_or(defaults.manual),
+                plugins: plugins::documented(&plugins::search_path(), plugins),
+                ..defaults
+            };
+            mangen(&dir, &header, gzip, single, force)?;
+        }
+        Task::Completions { out, shell } => {
+            let dir = out.unwrap_or_else(|| target_dir().join("completions"));
+            let shells = if shell.is_empty() {
+                CompletionShell::value_variants().to_vec()
+            } else {
+                shell
+            };
+            completions(&dir, &shells)?;
+            println!("Generated shell completions to {}", dir.display());
+        }
+        Task::Dist { target } => {
+            for archive in dist::dist(target.as_deref())? {
+                println!("{}  {}", dist::sha256(&archive)?, archive.display());
+            }
+        }
+    }
+    Ok(())
+}
+
+// English, the language of the completions and of the top-level pages.
+fn command() -> clap::Command {
+    i18n::localize(Cli::command(), Lang::En)
+}
+
+// Prints "N pages updated, M unchanged", or nothing when no file changed.
+fn mangen(
+    dir: &Path,
+    header: &man::Header,
+    gzip: bool,
+    single: bool,
+    force: bool,
+) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+
+    // The stamp holds the hash of the inputs and the files written from them.
+    let stamp_file = dir.join(".mangen-stamp");
+    let hash = format!("{:016x}", inputs_hash(header, gzip, single)?);
+    if !force {
+        let stamp = fs::read_to_string(&stamp_file).unwrap_or_default();
+        let mut lines = stamp.lines();
+        if lines.next() == Some(hash.as_str()) && lines.all(|file| dir.join(file).is_file()) {
+            return Ok(());
+        }
+    }
+
+    // A page for the root and all subcommands recursively, using the same
+    // page names `mycli docs` looks up at runtime, or just the root page.
+    let render = |cmd: clap::Command| {
+        if single {
+            Ok(vec![man::render_single(cmd, header)?])
+        } else {
+            man::render_all(cmd, header)
+        }
+    };
+    // English pages go straight into `dir`, translations into
+    // `<dir>/<lang>/man<section>/` where man(1) finds them for a matching
+    // locale. Untranslated strings fall back to English.
+    let mut pages = Vec::new();
+    for lang in Lang::ALL {
+        for page in render(i18n::localize(Cli::command(), lang))? {
+            let dest = match man::lang_dir(lang) {
+                Some(lang_dir) => dir.join(lang_dir).join(format!("man{}", page.section)),
+                None => dir.to_owned(),
+            };
+            fs::create_dir_all(&dest)?;
+            let path = dest.join(&page.file_name);
+            let changed = man::write_if_changed(&path, &page.contents)?;
+            pages.push(man::Page {
+                path,
+                section: page.section,
+                changed,
+            });
+        }
+    }
+
+    // The build-time twin of `mycli --dump-cli-json`, for tools that should
+    // not run the binary.
+    let schema = dir.join(introspect::SCHEMA_FILE);
+    let schema_changed =
+        man::write_if_changed(&schema, introspect::to_json(Cli::command()).as_bytes())?;
+
+    if gzip {
+        for page in &mut pages {
+            page.changed |= gzip_page(&page.path)?;
+        }
+    }
+
+    let updated = pages.iter().filter(|page| page.changed).count();
+    if schema_changed {
+        println!("Updated {}", schema.display());
+    }
+    if updated > 0 {
+        println!(
+            "{updated} pages updated, {} unchanged in {}",
+            pages.len() - updated,
+            dir.display()
+        );
+    }
+    let mut stamp = format!("{hash}\n{}", introspect::SCHEMA_FILE);
+    for page in &pages {
+        let name = page.path.strip_prefix(dir).expect("pages are in dir");
+        stamp.push('\n');
+        stamp.push_str(&name.to_string_lossy());
+        if gzip {
+            stamp.push_str(&format!("\n{}.gz", name.to_string_lossy()));
+        }
+    }
+    stamp.push('\n');
+    fs::write(stamp_file, stamp)
+}
+
+// Everything the pages are rendered from: the library sources (the CLI
+// definition in src/cli.rs and the page layout around it), Cargo.lock,
+// which pins clap and clap_mangen, and the options of this run, including
+// the plugins and their descriptions.
+fn inputs_hash(header: &man::Header, gzip: bool, single: bool) -> io::Result<u64> {
+    let root = workspace_dir();
+    let mut files: Vec<PathBuf> = fs::read_dir(root.join("src"))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<_>>()?;
+    files.sort();
+    files.push(root.join("Cargo.lock"));
+
+    let mut hasher = DefaultHasher::new();
+    for file in &files {
+        file.hash(&mut hasher);
+        fs::read(file)?.hash(&mut hasher);
+    }
+    header.hash(&mut hasher);
+    (gzip, single).hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+// Completion scripts named per shell convention: `mycli.bash`, `_mycli` (zsh),
+// `mycli.fish`, `_mycli.ps1`, `mycli.elv`, `mycli.nu` and `mycli.ts` (a Fig
+// spec).
+fn completions(dir: &Path, shells: &[CompletionShell]) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let mut cmd = without_hidden(&command());
+    let bin_name = cmd.get_name().to_owned();
+    for shell in shells {
+        let (cmd, name) = (&mut cmd, &bin_name);
+        match shell {
+            CompletionShell::Bash => clap_complete::generate_to(Shell::Bash, cmd, name, dir),
+            CompletionShell::Zsh => clap_complete::generate_to(Shell::Zsh, cmd, name, dir),
+            CompletionShell::Fish => clap_complete::generate_to(Shell::Fish, cmd, name, dir),
+            CompletionShell::PowerShell => {
+                clap_complete::generate_to(Shell::PowerShell, cmd, name, dir)
+            }
+            CompletionShell::Elvish => clap_complete::generate_to(Shell::Elvish, cmd, name, dir),
+            CompletionShell::Nushell => clap_complete::generate_to(Nushell, cmd, name, dir),
+            CompletionShell::Fig => clap_complete::generate_to(Fig, cmd, name, dir),
+        }?;
+    }
+    Ok(())
+}
+
+// `cmd` without its hidden arguments and subcommands. `hide` only keeps them
+// out of the help, and clap_complete offers them like any other. clap cannot
+// remove an argument or subcommand from a command, so each command is built
+// again with every other setting carried over.
+fn without_hidden(cmd: &clap::Command) -> clap::Command {
+    let shown = |id: &clap::Id| {
+        cmd.get_arguments()
+            .any(|a| a.get_id() == id && !a.is_hide_set())
+    };
+    let groups = cmd.get_groups().cloned().map(|mut group| {
+        clap::ArgGroup::new(group.get_id().clone())
+            .args(group.get_args().filter(|id| shown(id)).cloned())
+            .multiple(group.is_multiple())
+            .required(group.is_required_set())
+    });
+    let hidden_aliases =
+        |all: Vec<char>, shown: Vec<char>| all.into_iter().filter(move |c| !shown.contains(c));
+    let mut visible = clap::Command::new(cmd.get_name().to_owned())
+        .args(cmd.get_arguments().filter(|a| !a.is_hide_set()).cloned())
+        .groups(groups)
+        .subcommands(
+            cmd.get_subcommands()
+                .filter(|s| !s.is_hide_set())
+                .map(without_hidden),
+        )
+        .aliases(cmd.get_aliases().map(str::to_owned))
+        .visible_aliases(cmd.get_visible_aliases().map(str::to_owned))
+        .short_flag_aliases(hidden_aliases(
+            cmd.get_all_short_flag_aliases().collect(),
+            cmd.get_visible_short_flag_aliases().collect(),
+        ))
+        .visible_short_flag_aliases(cmd.get_visible_short_flag_aliases())
+        .long_flag_aliases(
+            cmd.get_all_long_flag_aliases()
+                .filter(|a| !cmd.get_visible_long_flag_aliases().any(|v| v == *a))
+                .map(str::to_owned),
+        )
+        .visible_long_flag_aliases(cmd.get_visible_long_flag_aliases().map(str::to_owned))
+        .display_order(cmd.get_display_order())
+        .color(cmd.get_color())
+        .styles(cmd.get_styles().clone())
+        .no_binary_name(cmd.is_no_binary_name_set())
+        .dont_delimit_trailing_values(cmd.is_dont_delimit_trailing_values_set())
+        .disable_version_flag(cmd.is_disable_version_flag_set())
+        .propagate_version(cmd.is_propagate_version_set())
+        .next_line_help(cmd.is_next_line_help_set())
+        .disable_help_flag(cmd.is_disable_help_flag_set())
+        .disable_help_subcommand(cmd.is_disable_help_subcommand_set())
+        .disable_colored_help(cmd.is_disable_colored_help_set())
+        .dont_collapse_args_in_usage(cmd.is_dont_collapse_args_in_usage_set())
+        .arg_required_else_help(cmd.is_arg_required_else_help_set())
+        .allow_negative_numbers(cmd.is_allow_negative_numbers_set())
+        .trailing_var_arg(cmd.is_trailing_var_arg_set())
+        .allow_missing_positional(cmd.is_allow_missing_positional_set())
+        .flatten_help(cmd.is_flatten_help_set())
+        .subcommand_required(cmd.is_subcommand_required_set())
+        .allow_external_subcommands(cmd.is_allow_external_subcommands_set())
+        .args_conflicts_with_subcommands(cmd.is_args_conflicts_with_subcommands_set())
+        .args_override_self(cmd.is_args_override_self())
+        .subcommand_precedence_over_arg(cmd.is_subcommand_precedence_over_arg_set())
+        .subcommand_negates_reqs(cmd.is_subcommand_negates_reqs_set())
+        .multicall(cmd.is_multicall_set());
+    let texts = [
+        (
+            cmd.get_about(),
+            clap::Command::about as fn(_, clap::builder::StyledStr) -> _,
+        ),
+        (cmd.get_long_about(), clap::Command::long_about),
+        (cmd.get_before_help(), clap::Command::before_help),
+        (cmd.get_before_long_help(), clap::Command::before_long_help),
+        (cmd.get_after_help(), clap::Command::after_help),
+        (cmd.get_after_long_help(), clap::Command::after_long_help),
+        (cmd.get_overridden_usage(), clap::Command::override_usage),
+    ];
+    for (text, set) in texts {
+        if let Some(text) = text {
+            visible = set(visible, text.clone());
+        }
+    }
+    let names = [
+        (
+            cmd.get_display_name(),
+            clap::Command::display_name as fn(_, String) -> _,
+        ),
+        (cmd.get_bin_name(), clap::Command::bin_name),
+        (cmd.get_author(), clap::Command::author),
+        (cmd.get_version(), clap::Command::version),
+        (cmd.get_long_version(), clap::Command::long_version),
+        (
+            cmd.get_subcommand_help_heading(),
+            clap::Command::subcommand_help_heading,
+        ),
+        (
+            cmd.get_subcommand_value_name(),
+            clap::Command::subcommand_value_name,
+        ),
+    ];
+    for (name, set) in names {
+        if let Some(name) = name {
+            visible = set(visible, name.to_owned());
+        }
+    }
+    if let Some(short) = cmd.get_short_flag() {
+        visible = visible.short_flag(short);
+    }
+    if let Some(long) = cmd.get_long_flag() {
+        visible = visible.long_flag(long.to_owned());
+    }
+    if let Some(parser) = cmd.get_external_subcommand_value_parser() {
+        visible = visible.external_subcommand_value_parser(parser.clone());
+    }
+    visible
+}
+
+// Compress `page` to `page.gz`. The header carries no file name and a fixed
+// mtime (SOURCE_DATE_EPOCH, else 0), so repeated runs are byte-identical.
+// Returns whether `page.gz` was written.
+fn gzip_page(page: &Path) -> io::Result<bool> {
+    let mtime = u32::try_from(source_date_epoch()).unwrap_or(u32::MAX);
+    let mut gz_name = page.as_os_str().to_owned();
+    gz_name.push(".gz");
+
+    man::write_if_changed(Path::new(&gz_name), &man::gzip(&fs::read(page)?, mtime)?)
+}
+
+// Timestamp for generated archives: SOURCE_DATE_EPOCH, else 0.
+fn source_date_epoch() -> u64 {
+    env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0)
+}
+
+fn workspace_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask lives in the workspace root")
+}
+
+// The workspace's target directory, honouring CARGO_TARGET_DIR.
+fn target_dir() -> PathBuf {
+    env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| workspace_dir().join("target"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+
+    // Every file below `dir`, recursively.
+    fn files(dir: &Path, out: &mut Vec<PathBuf>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                files(&path, out);
+            } else {
+                out.push(path);
+            }
+        }
+    }
+
+    #[test]
+    fn gzipped_pages_decompress_to_the_plain_ones() {
+        let dir = target_dir().join("tmp/xtask-mangen-gzip");
+        let _ = fs::remove_dir_all(&dir);
+        mangen(&dir, &man::Header::current(), true, false, true).unwrap();
+
+        let mut all = Vec::new();
+        files(&dir, &mut all);
+        let gzipped: Vec<&PathBuf> = all
+            .iter()
+            .filter(|f| f.extension().is_some_and(|ext| ext == "gz"))
+            .collect();
+        assert!(gzipped.len() > 10, "{all:?}");
+        for gz in gzipped {
+            let mut page = Vec::new();
+            flate2::read::GzDecoder::new(fs::File::open(gz).unwrap())
+                .read_to_end(&mut page)
+                .unwrap();
+            assert_eq!(
+                page,
+                fs::read(gz.with_extension("")).unwrap(),
+                "{}",
+                gz.display()
+            );
+        }
+
+        // The gzip header is fixed, so a second run rewrites nothing.
+        let before = fs::read(dir.join("mycli.1.gz")).unwrap();
+        mangen(&dir, &man::Header::current(), true, false, true).unwrap();
+        assert_eq!(before, fs::read(dir.join("mycli.1.gz")).unwrap());
+    }
+
+    #[test]
+    fn translations_go_into_section_directories() {
+        let dir = target_dir().join("tmp/xtask-mangen-lang");
+        let _ = fs::remove_dir_all(&dir);
+        mangen(&dir, &man::Header::current(), false, false, true).unwrap();
+
+        let root = fs::read_to_string(dir.join("de/man1/mycli.1")).unwrap();
+        let name = root
+            .lines()
+            .skip_while(|line| *line != ".SH NAME")
+            .nth(1)
+            .unwrap();
+        assert_eq!(
+            name,
+            "mycli \\- Beispiel\\-CLI mit verschachtelten Unterbefehlen und Manpage\\-Erzeugung"
+        );
+        assert!(dir.join("de/man8/mycli-server.8").is_file());
+        assert!(dir.join("mycli-server.8").is_file());
+        assert!(!dir.join("de/mycli.1").exists());
+    }
+
+    #[test]
+    fn completions_leave_hidden_items_out() {
+        let dir = target_dir().join("tmp/xtask-completions");
+        let _ = fs::remove_dir_all(&dir);
+        completions(&dir, CompletionShell::value_variants()).unwrap();
+        let mut scripts = Vec::new();
+        files(&dir, &mut scripts);
+        assert_eq!(scripts.len(), CompletionShell::value_variants().len());
+        for script in scripts {
+            let text = fs::read_to_string(&script).unwrap();
+            assert!(text.contains("config"), "{}", script.display());
+            assert!(!text.contains("dump-cli-json"), "{}", script.display());
+        }
+    }
+
+    #[test]
+    fn nushell_and_fig_complete_config_set() {
+        let dir = target_dir().join("tmp/xtask-completions-nu-fig");
+        let _ = fs::remove_dir_all(&dir);
+        completions(&dir, &[CompletionShell::Nushell, CompletionShell::Fig]).unwrap();
+
+        let nu = fs::read_to_string(dir.join("mycli.nu")).unwrap();
+        let set = nu
+            .split("export extern \"mycli config set\" [")
+            .nth(1)
+            .expect("nushell declares `mycli config set`");
+        assert!(set.contains("--global"), "{nu}");
+
+        let fig = fs::read_to_string(dir.join("mycli.ts")).unwrap();
+        let config = fig
+            .split("name: \"config\",")
+            .nth(1)
+            .expect("the Fig spec has `config`");
+        assert!(
+            config.contains("name: \"set\",\n          description: \"Set a configuration value\""),
+            "{fig}"
+        );
+        assert!(config.contains("name: \"--global\""), "{fig}");
+    }
+
+    #[test]
+    fn hidden_items_are_pruned_everywhere_in_the_tree() {
+        let cmd = clap::Command::new("mycli")
+            .version("1.0")
+            .after_help("Examples: ...")
+            .arg_required_else_help(true)
+            .arg(clap::Arg::new("shown").long("shown"))
+            .arg(clap::Arg::new("secret").long("secret").hide(true))
+            .group(clap::ArgGroup::new("flags").args(["shown", "secret"]))
+            .subcommand(clap::Command::new("hidden").hide(true))
+            .subcommand(
+                clap::Command::new("config")
+                    .about("Manage configuration values")
+                    .visible_alias("cfg")
+                    .alias("conf")
+                    .subcommand_required(true)
+                    .subcommand(
+                        clap::Command::new("get")
+                            .arg(clap::Arg::new("secret").long("secret").hide(true)),
+                    ),
+            );
+        let visible = without_hidden(&cmd);
+
+        let names = |cmd: &clap::Command| -> Vec<String> {
+            cmd.get_arguments()
+                .map(|a| a.get_id().to_string())
+                .chain(cmd.get_subcommands().map(|s| s.get_name().to_owned()))
+                .collect()
+        };
+        assert_eq!(names(&visible), ["shown", "config"]);
+        let config = visible.find_subcommand("config").unwrap();
+        assert_eq!(
+            config.get_about().map(ToString::to_string).as_deref(),
+            Some("Manage configuration values")
+        );
+        assert!(names(config.find_subcommand("get").unwrap()).is_empty());
+
+        // Everything else is kept.
+        assert_eq!(visible.get_version(), Some("1.0"));
+        assert_eq!(
+            visible.get_after_help().map(ToString::to_string).as_deref(),
+            Some("Examples: ...")
+        );
+        assert!(visible.is_arg_required_else_help_set());
+        let flags: Vec<_> = visible.get_groups().flat_map(|g| g.get_args()).collect();
+        assert_eq!(flags, ["shown"]);
+        assert!(config.is_subcommand_required_set());
+        assert_eq!(
+            config.get_all_aliases().collect::<Vec<_>>(),
+            ["conf", "cfg"]
+        );
+        assert_eq!(config.get_visible_aliases().collect::<Vec<_>>(), ["cfg"]);
+    }
+}